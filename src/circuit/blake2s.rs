@@ -252,12 +252,26 @@ fn blake2s_compression<E: Engine, CS: ConstraintSystem<E>>(
         END FUNCTION.
 */
 
+fn read_u32_le(bytes: &[u8]) -> u32 {
+    assert_eq!(bytes.len(), 4);
+
+    (bytes[0] as u32) |
+    ((bytes[1] as u32) << 8) |
+    ((bytes[2] as u32) << 16) |
+    ((bytes[3] as u32) << 24)
+}
+
+/// Computes the 256-bit BLAKE2s hash of `input`, which
+/// must be a whole number of bytes, under the given
+/// 8-byte `personalization`.
 pub fn blake2s<E: Engine, CS: ConstraintSystem<E>>(
     mut cs: CS,
-    input: &[Boolean<CS::Variable>]
+    input: &[Boolean<CS::Variable>],
+    personalization: &[u8]
 ) -> Result<Vec<Boolean<CS::Variable>>, SynthesisError>
 {
     assert!(input.len() % 8 == 0);
+    assert_eq!(personalization.len(), 8);
 
     let mut h = Vec::with_capacity(8);
     h.push(UInt32::constant(0x6A09E667 ^ 0x01010000 ^ 32));
@@ -266,8 +280,8 @@ pub fn blake2s<E: Engine, CS: ConstraintSystem<E>>(
     h.push(UInt32::constant(0xA54FF53A));
     h.push(UInt32::constant(0x510E527F));
     h.push(UInt32::constant(0x9B05688C));
-    h.push(UInt32::constant(0x1F83D9AB));
-    h.push(UInt32::constant(0x5BE0CD19));
+    h.push(UInt32::constant(0x1F83D9AB ^ read_u32_le(&personalization[0..4])));
+    h.push(UInt32::constant(0x5BE0CD19 ^ read_u32_le(&personalization[4..8])));
 
     let mut blocks: Vec<Vec<UInt32<CS::Variable>>> = vec![];
 
@@ -320,7 +334,7 @@ mod test {
     fn test_blake2s_constraints() {
         let mut cs = TestConstraintSystem::<Bls12>::new();
         let input_bits: Vec<_> = (0..512).map(|i| AllocatedBit::alloc(cs.namespace(|| format!("input bit {}", i)), Some(true)).unwrap().into()).collect();
-        blake2s(&mut cs, &input_bits).unwrap();
+        blake2s(&mut cs, &input_bits, &[0; 8]).unwrap();
         assert!(cs.is_satisfied());
         assert_eq!(cs.num_constraints(), 21792);
     }
@@ -337,7 +351,7 @@ mod test {
           .chain((0..512)
                         .map(|i| AllocatedBit::alloc(cs.namespace(|| format!("input bit {}", i)), Some(true)).unwrap().into()))
           .collect();
-        blake2s(&mut cs, &input_bits).unwrap();
+        blake2s(&mut cs, &input_bits, &[0; 8]).unwrap();
         assert!(cs.is_satisfied());
         assert_eq!(cs.num_constraints(), 21792);
     }
@@ -347,10 +361,47 @@ mod test {
         let mut cs = TestConstraintSystem::<Bls12>::new();
         let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
         let input_bits: Vec<_> = (0..512).map(|_| Boolean::constant(rng.gen())).collect();
-        blake2s(&mut cs, &input_bits).unwrap();
+        blake2s(&mut cs, &input_bits, &[0; 8]).unwrap();
         assert_eq!(cs.num_constraints(), 0);
     }
 
+    fn test_vector(input: &[u8], personalization: &[u8], expected: &str) {
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let mut input_bits = vec![];
+
+        for (byte_i, input_byte) in input.iter().enumerate() {
+            for bit_i in (0..8).rev() {
+                let cs = cs.namespace(|| format!("input bit {} {}", byte_i, bit_i));
+
+                input_bits.push(AllocatedBit::alloc(cs, Some((input_byte >> bit_i) & 1u8 == 1u8)).unwrap().into());
+            }
+        }
+
+        let r = blake2s(&mut cs, &input_bits, personalization).unwrap();
+
+        assert!(cs.is_satisfied());
+
+        let expected: Vec<u8> = (0..32).map(|i| u8::from_str_radix(&expected[i*2..i*2+2], 16).unwrap()).collect();
+        let mut s = expected.iter()
+                            .flat_map(|&byte| (0..8).rev().map(move |i| (byte >> i) & 1u8 == 1u8));
+
+        for b in r {
+            assert_eq!(s.next().unwrap(), b.get_value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_blake2s_test_vectors() {
+        // From RFC 7693, Appendix B.
+        test_vector(b"abc", &[0; 8], "508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982");
+
+        // Personalized hashes.
+        test_vector(b"abc", b"Zcash_nf", "752d77e9ad87e5717c888e30211e57a95f684cf3edcea04ffcb215caee336578");
+        test_vector(b"", b"12345678", "c59f682376d137f3f255e671e207d1f2374ebe504e9314208a52d9f88d69e8c8");
+        test_vector(&(0..100).collect::<Vec<u8>>(), b"Zcash_nf", "8b9aefcd388d02b4529031fef49ef72a5859e569f6fe2e6c7d4e09a26eb3c464");
+    }
+
     #[test]
     fn test_blake2s() {
         let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
@@ -377,7 +428,7 @@ mod test {
                 }
             }
 
-            let r = blake2s(&mut cs, &input_bits).unwrap();
+            let r = blake2s(&mut cs, &input_bits, &[0; 8]).unwrap();
 
             assert!(cs.is_satisfied());
 
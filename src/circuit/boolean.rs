@@ -2,7 +2,6 @@ use pairing::{
     Engine,
     Field,
     PrimeField,
-    PrimeFieldRepr,
     BitIterator
 };
 
@@ -16,6 +15,8 @@ use super::{
     Assignment
 };
 
+use super::num::{AllocatedNum, Num};
+
 /// Represents a variable in the constraint system which is guaranteed
 /// to be either zero or one.
 #[derive(Clone)]
@@ -66,6 +67,31 @@ impl<Var: Copy> AllocatedBit<Var> {
         })
     }
 
+    /// Constrains an existing variable to be boolean,
+    /// rather than allocating a new one.
+    pub fn from_variable<E, CS>(
+        mut cs: CS,
+        var: Var,
+        value: Option<bool>,
+    ) -> Result<Self, SynthesisError>
+        where E: Engine,
+              CS: ConstraintSystem<E, Variable=Var>
+    {
+        // Constrain: (1 - a) * a = 0
+        let one = cs.one();
+        cs.enforce(
+            || "boolean constraint",
+            LinearCombination::zero() + one - var,
+            LinearCombination::zero() + var,
+            LinearCombination::zero()
+        );
+
+        Ok(AllocatedBit {
+            variable: var,
+            value: value
+        })
+    }
+
     /// Performs an XOR operation over the two operands, returning
     /// an `AllocatedBit`.
     pub fn xor<E, CS>(
@@ -249,6 +275,9 @@ pub enum Boolean<Var> {
 }
 
 impl<Var: Copy> Boolean<Var> {
+    /// Enforces that `a` and `b` are equal, using a single
+    /// linear constraint. If both are constant, no constraint
+    /// is added, and unequal constants are an error.
     pub fn enforce_equal<E, CS>(
         mut cs: CS,
         a: &Self,
@@ -257,9 +286,35 @@ impl<Var: Copy> Boolean<Var> {
         where E: Engine,
               CS: ConstraintSystem<E, Variable=Var>
     {
-        let c = Self::xor(&mut cs, a, b)?;
+        match (a, b) {
+            (&Boolean::Constant(a), &Boolean::Constant(b)) => {
+                if a == b {
+                    Ok(())
+                } else {
+                    Err(SynthesisError::AssignmentMissing)
+                }
+            },
+            _ => {
+                let one = cs.one();
+                let mut minus_one = E::Fr::one();
+                minus_one.negate();
+
+                let mut lc = a.lc(one, E::Fr::one());
+                for &(var, coeff) in b.lc::<E>(one, minus_one).as_ref() {
+                    lc = lc + (coeff, var);
+                }
+
+                // (a - b) * 1 = 0
+                cs.enforce(
+                    || "equality constraint",
+                    lc,
+                    LinearCombination::zero() + one,
+                    LinearCombination::zero()
+                );
 
-        Self::enforce_nand(&mut cs, &[c])
+                Ok(())
+            }
+        }
     }
 
     pub fn get_value(&self) -> Option<bool> {
@@ -270,6 +325,21 @@ impl<Var: Copy> Boolean<Var> {
         }
     }
 
+    /// Returns true if this boolean is a constant
+    /// rather than an allocated variable.
+    pub fn is_constant(&self) -> bool {
+        self.get_constant_value().is_some()
+    }
+
+    /// Returns the value of this boolean if it is a
+    /// constant, and `None` if it is allocated.
+    pub fn get_constant_value(&self) -> Option<bool> {
+        match self {
+            &Boolean::Constant(c) => Some(c),
+            _ => None
+        }
+    }
+
     pub fn lc<E: Engine>(&self, one: Var, coeff: E::Fr) -> LinearCombination<Var, E>
     {
         match self {
@@ -289,6 +359,36 @@ impl<Var: Copy> Boolean<Var> {
         }
     }
 
+    /// Sums the weighted booleans `coeff * bit`
+    /// into a single linear combination.
+    pub fn lc_sum<E: Engine>(bits: &[(E::Fr, Self)], one: Var) -> LinearCombination<Var, E>
+    {
+        bits.iter().fold(LinearCombination::<Var, E>::zero(), |lc, &(coeff, ref bit)| {
+            lc + &bit.lc(one, coeff)
+        })
+    }
+
+    /// Returns this boolean as a number that is one if
+    /// it is true and zero otherwise. This is free for
+    /// an allocated bit, and costs one constraint
+    /// otherwise.
+    pub fn to_num<E: Engine, CS>(
+        &self,
+        cs: CS
+    ) -> Result<AllocatedNum<E, Var>, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        match self {
+            &Boolean::Constant(true) => AllocatedNum::one(cs),
+            &Boolean::Is(ref v) => Ok(AllocatedNum::from_bit(v)),
+            _ => {
+                let one = cs.one();
+                Num::zero().add_bool_with_coeff(one, self, E::Fr::one())
+                           .into_allocated_num(cs)
+            }
+        }
+    }
+
     /// Construct a boolean from a known constant
     pub fn constant(b: bool) -> Self {
         Boolean::Constant(b)
@@ -330,6 +430,27 @@ impl<Var: Copy> Boolean<Var> {
         }
     }
 
+    /// Perform XOR over three boolean operands. This
+    /// costs two constraints when none of them are
+    /// constant, one fewer for each constant operand.
+    /// A single constraint is not possible, as a ^ b ^ c
+    /// has the degree 3 term 4abc.
+    pub fn xor3<'a, E, CS>(
+        mut cs: CS,
+        a: &'a Self,
+        b: &'a Self,
+        c: &'a Self
+    ) -> Result<Self, SynthesisError>
+        where E: Engine,
+              CS: ConstraintSystem<E, Variable=Var>
+    {
+        // Constants are folded by `xor` itself, so the
+        // order of the operands does not matter.
+        let tmp = Boolean::xor(cs.namespace(|| "first xor"), a, b)?;
+
+        Boolean::xor(cs.namespace(|| "second xor"), &tmp, c)
+    }
+
     /// Perform AND over two boolean operands
     pub fn and<'a, E, CS>(
         cs: CS,
@@ -359,6 +480,8 @@ impl<Var: Copy> Boolean<Var> {
         }
     }
 
+    /// Computes the AND of all of the operands. The
+    /// empty conjunction is true.
     pub fn kary_and<E, CS>(
         mut cs: CS,
         bits: &[Self]
@@ -366,10 +489,12 @@ impl<Var: Copy> Boolean<Var> {
         where E: Engine,
               CS: ConstraintSystem<E, Variable=Var>
     {
-        assert!(bits.len() > 0);
         let mut bits = bits.iter();
 
-        let mut cur: Self = bits.next().unwrap().clone();
+        let mut cur: Self = match bits.next() {
+            Some(bit) => bit.clone(),
+            None => return Ok(Boolean::constant(true))
+        };
 
         let mut i = 0;
         while let Some(next) = bits.next() {
@@ -381,6 +506,66 @@ impl<Var: Copy> Boolean<Var> {
         Ok(cur)
     }
 
+    /// Computes the AND of all of the operands, as the
+    /// counterpart of `or_many`. The empty conjunction
+    /// is true.
+    pub fn and_many<E, CS>(
+        cs: CS,
+        bits: &[Self]
+    ) -> Result<Self, SynthesisError>
+        where E: Engine,
+              CS: ConstraintSystem<E, Variable=Var>
+    {
+        Self::kary_and(cs, bits)
+    }
+
+    /// Computes the OR of all of the operands. The
+    /// empty disjunction is false.
+    pub fn or_many<E, CS>(
+        cs: CS,
+        bits: &[Self]
+    ) -> Result<Self, SynthesisError>
+        where E: Engine,
+              CS: ConstraintSystem<E, Variable=Var>
+    {
+        // a OR b = NOT((NOT a) AND (NOT b))
+        let negated: Vec<_> = bits.iter().map(|b| b.not()).collect();
+
+        Ok(Self::and_many(cs, &negated)?.not())
+    }
+
+    /// Returns true if `a` and `b` are equal bit for bit.
+    /// The slices must be of the same length; the empty
+    /// slices are equal.
+    pub fn vec_eq<E, CS>(
+        mut cs: CS,
+        a: &[Self],
+        b: &[Self]
+    ) -> Result<Self, SynthesisError>
+        where E: Engine,
+              CS: ConstraintSystem<E, Variable=Var>
+    {
+        if a.len() != b.len() {
+            return Err(SynthesisError::AssignmentMissing);
+        }
+
+        if a.len() == 0 {
+            return Ok(Boolean::constant(true));
+        }
+
+        let mut eqs = Vec::with_capacity(a.len());
+        for (i, (a, b)) in a.iter().zip(b.iter()).enumerate() {
+            // a XNOR b = NOT(a XOR b)
+            eqs.push(Boolean::xor(
+                cs.namespace(|| format!("XNOR {}", i)),
+                a,
+                b
+            )?.not());
+        }
+
+        Self::kary_and(cs.namespace(|| "conjunction"), &eqs)
+    }
+
     /// Asserts that at least one operand is false.
     pub fn enforce_nand<E, CS>(
         mut cs: CS,
@@ -425,7 +610,7 @@ impl<Var: Copy> Boolean<Var> {
     /// Asserts that this bit representation is "in
     /// the field" when interpreted in big endian.
     pub fn enforce_in_field<E, CS, F: PrimeField>(
-        mut cs: CS,
+        cs: CS,
         bits: &[Self]
     ) -> Result<(), SynthesisError>
         where E: Engine,
@@ -433,26 +618,47 @@ impl<Var: Copy> Boolean<Var> {
     {
         assert_eq!(bits.len(), F::NUM_BITS as usize);
 
-        let mut a = bits.iter();
+        // Skip over unset bits at the beginning
+        let mut found_one = false;
+        let modulus = BitIterator::new(F::char()).filter(|&b| {
+            found_one |= b;
+            found_one
+        }).collect::<Vec<_>>();
+
+        Self::enforce_less_than(cs, bits, &modulus)
+    }
+
+    /// Asserts that this bit representation is less
+    /// than `modulus`, both interpreted in big endian.
+    pub fn enforce_less_than<E, CS>(
+        mut cs: CS,
+        bits: &[Self],
+        modulus: &[bool]
+    ) -> Result<(), SynthesisError>
+        where E: Engine,
+              CS: ConstraintSystem<E, Variable=Var>
+    {
+        assert_eq!(bits.len(), modulus.len());
+        assert!(modulus.iter().any(|&b| b), "modulus must be nonzero");
+
+        // b = modulus - 1
+        let mut b = modulus.to_vec();
+        for bit in b.iter_mut().rev() {
+            *bit = !*bit;
+            if !*bit {
+                break;
+            }
+        }
 
-        // b = char() - 1
-        let mut b = F::char();
-        b.sub_noborrow(&1.into());
+        let mut a = bits.iter();
 
-        // Runs of ones in r
+        // Runs of ones in b
         let mut last_run = Boolean::<Var>::constant(true);
         let mut current_run = vec![];
 
-        let mut found_one = false;
         let mut run_i = 0;
         let mut nand_i = 0;
-        for b in BitIterator::new(b) {
-            // Skip over unset bits at the beginning
-            found_one |= b;
-            if !found_one {
-                continue;
-            }
-
+        for b in b {
             let a = a.next().unwrap();
 
             if b {
@@ -473,7 +679,7 @@ impl<Var: Copy> Boolean<Var> {
                 }
 
                 // If `last_run` is true, `a` must be false, or it would
-                // not be in the field.
+                // not be less than the modulus.
                 //
                 // If `last_run` is false, `a` can be true or false.
                 //
@@ -486,10 +692,8 @@ impl<Var: Copy> Boolean<Var> {
             }
         }
 
-        // We should always end in a "run" of zeros, because
-        // the characteristic is an odd prime. So, this should
-        // be empty.
-        assert_eq!(current_run.len(), 0);
+        // Any trailing run of ones in b needs no constraints:
+        // whatever those bits of `a` are, it can't exceed b.
 
         Ok(())
     }
@@ -501,14 +705,29 @@ impl<Var> From<AllocatedBit<Var>> for Boolean<Var> {
     }
 }
 
+/// Reverses the order of `bits`, converting between
+/// big-endian and little-endian bit order.
+pub fn reverse_bits<Var: Copy>(bits: &[Boolean<Var>]) -> Vec<Boolean<Var>> {
+    bits.iter().rev().cloned().collect()
+}
+
+/// Reverses the order of the bytes in `bits`, which
+/// must be a whole number of bytes, while keeping the
+/// order of the bits within each byte.
+pub fn reverse_byte_order<Var: Copy>(bits: &[Boolean<Var>]) -> Vec<Boolean<Var>> {
+    assert!(bits.len() % 8 == 0);
+
+    bits.chunks(8).rev().flat_map(|byte| byte.iter().cloned()).collect()
+}
+
 #[cfg(test)]
 mod test {
     use rand::{SeedableRng, Rand, XorShiftRng};
-    use bellman::{ConstraintSystem};
+    use bellman::{ConstraintSystem, LinearCombination};
     use pairing::bls12_381::{Bls12, Fr};
     use pairing::{Field, PrimeField, PrimeFieldRepr, BitIterator};
     use ::circuit::test::*;
-    use super::{AllocatedBit, Boolean};
+    use super::{AllocatedBit, Boolean, reverse_bits, reverse_byte_order};
 
     #[test]
     fn test_allocated_bit() {
@@ -642,6 +861,79 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_enforce_equal_all_kinds() {
+        #[derive(Copy, Clone)]
+        enum Kind {
+            Constant,
+            Is,
+            Not
+        }
+
+        fn make(cs: &mut TestConstraintSystem<Bls12>, name: &str, kind: Kind, value: bool) -> Boolean<Variable> {
+            match kind {
+                Kind::Constant => Boolean::constant(value),
+                Kind::Is => Boolean::from(AllocatedBit::alloc(cs.namespace(|| name), Some(value)).unwrap()),
+                Kind::Not => Boolean::from(AllocatedBit::alloc(cs.namespace(|| name), Some(!value)).unwrap()).not()
+            }
+        }
+
+        let kinds = [Kind::Constant, Kind::Is, Kind::Not];
+
+        for &a_kind in &kinds {
+            for &b_kind in &kinds {
+                for &a_value in &[false, true] {
+                    for &b_value in &[false, true] {
+                        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+                        let a = make(&mut cs, "a", a_kind, a_value);
+                        let b = make(&mut cs, "b", b_kind, b_value);
+                        let before = cs.num_constraints();
+
+                        let res = Boolean::enforce_equal(&mut cs, &a, &b);
+
+                        match (a_kind, b_kind) {
+                            (Kind::Constant, Kind::Constant) => {
+                                assert_eq!(res.is_ok(), a_value == b_value);
+                                assert_eq!(cs.num_constraints(), before);
+                            },
+                            _ => {
+                                assert!(res.is_ok());
+                                assert_eq!(cs.num_constraints(), before + 1);
+
+                                if a_value == b_value {
+                                    assert!(cs.is_satisfied());
+                                } else {
+                                    assert_eq!(cs.which_is_unsatisfied().unwrap(), "equality constraint");
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_boolean_is_constant() {
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let a = Boolean::from(AllocatedBit::alloc(&mut cs, Some(true)).unwrap());
+
+        assert!(!a.is_constant());
+        assert!(!a.not().is_constant());
+        assert_eq!(a.get_constant_value(), None);
+        assert_eq!(a.not().get_constant_value(), None);
+
+        for &b in &[false, true] {
+            let c = Boolean::<Variable>::constant(b);
+
+            assert!(c.is_constant());
+            assert_eq!(c.get_constant_value(), Some(b));
+            assert_eq!(c.not().get_constant_value(), Some(!b));
+        }
+    }
+
     #[test]
     fn test_boolean_negation() {
         let mut cs = TestConstraintSystem::<Bls12>::new();
@@ -699,6 +991,61 @@ mod test {
         NegatedAllocatedFalse
     }
 
+    #[test]
+    fn test_boolean_xor3() {
+        let variants = [
+            OperandType::True,
+            OperandType::False,
+            OperandType::AllocatedTrue,
+            OperandType::AllocatedFalse,
+            OperandType::NegatedAllocatedTrue,
+            OperandType::NegatedAllocatedFalse
+        ];
+
+        for first_operand in variants.iter().cloned() {
+            for second_operand in variants.iter().cloned() {
+                for third_operand in variants.iter().cloned() {
+                    let mut cs = TestConstraintSystem::<Bls12>::new();
+
+                    let mut expected = false;
+                    let mut num_constant = 0;
+
+                    let operands: Vec<_> = [first_operand, second_operand, third_operand].iter().enumerate().map(|(i, &operand)| {
+                        let cs = cs.namespace(|| format!("operand {}", i));
+
+                        let (b, value) = match operand {
+                            OperandType::True => (Boolean::constant(true), true),
+                            OperandType::False => (Boolean::constant(false), false),
+                            OperandType::AllocatedTrue => (Boolean::from(AllocatedBit::alloc(cs, Some(true)).unwrap()), true),
+                            OperandType::AllocatedFalse => (Boolean::from(AllocatedBit::alloc(cs, Some(false)).unwrap()), false),
+                            OperandType::NegatedAllocatedTrue => (Boolean::from(AllocatedBit::alloc(cs, Some(true)).unwrap()).not(), false),
+                            OperandType::NegatedAllocatedFalse => (Boolean::from(AllocatedBit::alloc(cs, Some(false)).unwrap()).not(), true),
+                        };
+
+                        expected ^= value;
+                        if b.is_constant() {
+                            num_constant += 1;
+                        }
+
+                        b
+                    }).collect();
+
+                    let num_inputs = cs.num_constraints();
+
+                    let c = Boolean::xor3(cs.namespace(|| "xor3"), &operands[0], &operands[1], &operands[2]).unwrap();
+
+                    assert!(cs.is_satisfied());
+                    assert_eq!(c.get_value(), Some(expected));
+
+                    if num_constant == 3 {
+                        assert_eq!(c.get_constant_value(), Some(expected));
+                    }
+                    assert_eq!(cs.num_constraints() - num_inputs, 2 - ::std::cmp::min(num_constant, 2));
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_boolean_xor() {
         let variants = [
@@ -1128,4 +1475,214 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_vec_eq() {
+        let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for len in 1..10 {
+            for _ in 0..20 {
+                let mut cs = TestConstraintSystem::<Bls12>::new();
+
+                let a_values: Vec<bool> = (0..len).map(|_| bool::rand(&mut rng)).collect();
+                let mut b_values = a_values.clone();
+                let differ = bool::rand(&mut rng);
+                if differ {
+                    b_values[0] = !b_values[0];
+                }
+
+                // Mix constants and allocated bits.
+                let a: Vec<_> = a_values.iter().enumerate().map(|(i, &v)| {
+                    Boolean::from(AllocatedBit::alloc(cs.namespace(|| format!("a {}", i)), Some(v)).unwrap())
+                }).collect();
+                let b: Vec<_> = b_values.iter().enumerate().map(|(i, &v)| {
+                    if i % 2 == 0 {
+                        Boolean::constant(v)
+                    } else {
+                        Boolean::from(AllocatedBit::alloc(cs.namespace(|| format!("b {}", i)), Some(v)).unwrap())
+                    }
+                }).collect();
+
+                let r = Boolean::vec_eq(cs.namespace(|| "vec eq"), &a, &b).unwrap();
+
+                assert!(cs.is_satisfied());
+                assert_eq!(r.get_value().unwrap(), !differ);
+            }
+        }
+
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        match Boolean::<Variable>::vec_eq(&mut cs, &[], &[]).unwrap() {
+            Boolean::Constant(true) => {},
+            _ => panic!("empty slices should be equal")
+        }
+
+        assert!(Boolean::<Variable>::vec_eq(&mut cs, &[Boolean::constant(true)], &[]).is_err());
+        assert_eq!(cs.num_constraints(), 0);
+    }
+
+    #[test]
+    fn test_and_many_or_many() {
+        let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for len in 0..12 {
+            for _ in 0..20 {
+                let mut cs = TestConstraintSystem::<Bls12>::new();
+
+                let values: Vec<bool> = (0..len).map(|_| bool::rand(&mut rng)).collect();
+
+                // Mix constants and allocated bits.
+                let bits: Vec<_> = values.iter().enumerate().map(|(i, &v)| {
+                    if bool::rand(&mut rng) {
+                        Boolean::constant(v)
+                    } else {
+                        Boolean::from(AllocatedBit::alloc(cs.namespace(|| format!("bit {}", i)), Some(v)).unwrap())
+                    }
+                }).collect();
+
+                let and = Boolean::and_many(cs.namespace(|| "and many"), &bits).unwrap();
+                let or = Boolean::or_many(cs.namespace(|| "or many"), &bits).unwrap();
+
+                assert!(cs.is_satisfied());
+                assert_eq!(and.get_value().unwrap(), values.iter().all(|&v| v));
+                assert_eq!(or.get_value().unwrap(), values.iter().any(|&v| v));
+
+                if len == 0 {
+                    match (and, or) {
+                        (Boolean::Constant(true), Boolean::Constant(false)) => {},
+                        _ => panic!("unexpected result for empty slice")
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_enforce_less_than() {
+        let num_bits = 6;
+
+        for modulus in 1..(1 << num_bits) + 1 {
+            // Pad the modulus to one more bit than the operand
+            // so that 2^num_bits is representable.
+            let modulus_bits = (0..num_bits + 1).rev()
+                                                .map(|i| (modulus >> i) & 1 == 1)
+                                                .collect::<Vec<_>>();
+
+            for value in 0..(1 << num_bits) {
+                let mut cs = TestConstraintSystem::<Bls12>::new();
+
+                let bits = (0..num_bits + 1).rev().map(|i| {
+                    Boolean::from(AllocatedBit::alloc(
+                        cs.namespace(|| format!("bit {}", i)),
+                        Some((value >> i) & 1 == 1)
+                    ).unwrap())
+                }).collect::<Vec<_>>();
+
+                Boolean::enforce_less_than(&mut cs, &bits, &modulus_bits).unwrap();
+
+                assert_eq!(cs.is_satisfied(), value < modulus);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lc_sum() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for i in 0..64 {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            // Every kind of boolean, with both values
+            let a = AllocatedBit::alloc(cs.namespace(|| "a"), Some(i & 1 == 1)).unwrap();
+            let b = AllocatedBit::alloc(cs.namespace(|| "b"), Some(i & 2 == 2)).unwrap();
+            let bits = vec![
+                (Fr::rand(&mut rng), Boolean::from(a)),
+                (Fr::rand(&mut rng), Boolean::from(b).not()),
+                (Fr::rand(&mut rng), Boolean::constant(i & 4 == 4)),
+                (Fr::rand(&mut rng), Boolean::constant(i & 8 == 8)),
+                (Fr::rand(&mut rng), Boolean::constant(i & 16 == 16).not()),
+                (Fr::rand(&mut rng), Boolean::constant(i & 32 == 32))
+            ];
+
+            let mut expected = Fr::zero();
+            for &(coeff, ref bit) in &bits {
+                if bit.get_value().unwrap() {
+                    expected.add_assign(&coeff);
+                }
+            }
+
+            let one = cs.one();
+            let lc = Boolean::lc_sum::<Bls12>(&bits, one);
+            let sum = cs.alloc(|| "sum", || Ok(expected)).unwrap();
+            cs.enforce(
+                || "sum check",
+                lc,
+                LinearCombination::zero() + one,
+                LinearCombination::zero() + sum
+            );
+
+            assert!(cs.is_satisfied());
+            cs.set("sum", Fr::rand(&mut rng));
+            assert_eq!(cs.which_is_unsatisfied(), Some("sum check"));
+        }
+    }
+
+    #[test]
+    fn test_reverse_bits() {
+        // 0x12, 0x34, most significant bit first
+        let bits: Vec<Boolean<()>> = [
+            false, false, false, true, false, false, true, false,
+            false, false, true, true, false, true, false, false
+        ].iter().map(|&b| Boolean::constant(b)).collect();
+
+        let values = |bits: Vec<Boolean<()>>| {
+            bits.iter().map(|b| b.get_value().unwrap()).collect::<Vec<_>>()
+        };
+
+        // 0x2c, 0x48
+        assert_eq!(values(reverse_bits(&bits)), vec![
+            false, false, true, false, true, true, false, false,
+            false, true, false, false, true, false, false, false
+        ]);
+        assert!(reverse_bits::<()>(&[]).is_empty());
+
+        // 0x34, 0x12
+        assert_eq!(values(reverse_byte_order(&bits)), vec![
+            false, false, true, true, false, true, false, false,
+            false, false, false, true, false, false, true, false
+        ]);
+
+        // Both are involutions.
+        assert_eq!(values(reverse_bits(&reverse_bits(&bits))), values(bits.clone()));
+        assert_eq!(values(reverse_byte_order(&reverse_byte_order(&bits))), values(bits));
+    }
+
+    #[test]
+    fn test_to_num() {
+        for &b in &[false, true] {
+            let value = if b { Fr::one() } else { Fr::zero() };
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let bit = AllocatedBit::alloc(cs.namespace(|| "bit"), Some(b)).unwrap();
+
+            let n = Boolean::from(bit.clone()).to_num(cs.namespace(|| "is")).unwrap();
+            assert_eq!(n.get_value().unwrap(), value);
+            assert_eq!(cs.num_constraints(), 1);
+
+            let n = Boolean::from(bit).not().to_num(cs.namespace(|| "not")).unwrap();
+            assert_eq!(n.get_value().unwrap(), if b { Fr::zero() } else { Fr::one() });
+            assert_eq!(cs.num_constraints(), 2);
+
+            let n = Boolean::constant(b).to_num(cs.namespace(|| "constant")).unwrap();
+            assert_eq!(n.get_value().unwrap(), value);
+            assert_eq!(cs.num_constraints(), 3);
+
+            assert!(cs.is_satisfied());
+
+            // The negation is constrained.
+            cs.set("not/num", value);
+            assert_eq!(cs.which_is_unsatisfied(), Some("not/packing constraint"));
+        }
+    }
 }
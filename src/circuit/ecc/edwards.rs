@@ -0,0 +1,2101 @@
+use pairing::{
+    Engine,
+    Field,
+    SqrtField,
+    LegendreSymbol,
+    PrimeField,
+    PrimeFieldRepr
+};
+
+use bellman::{
+    SynthesisError,
+    ConstraintSystem,
+    LinearCombination
+};
+
+use super::super::{
+    Assignment
+};
+
+use super::super::num::AllocatedNum;
+
+use ::jubjub::{
+    JubjubEngine,
+    JubjubParams,
+    FixedGenerators,
+    PrimeOrder,
+    edwards
+};
+
+use super::super::lookup::{
+    lookup3_xy
+};
+
+use super::super::boolean::{Boolean, AllocatedBit};
+
+
+pub struct EdwardsPoint<E: Engine, Var> {
+    pub x: AllocatedNum<E, Var>,
+    pub y: AllocatedNum<E, Var>
+}
+
+impl<E: Engine, Var: Copy> Clone for EdwardsPoint<E, Var> {
+    fn clone(&self) -> Self {
+        EdwardsPoint {
+            x: self.x.clone(),
+            y: self.y.clone()
+        }
+    }
+}
+
+/// Perform a fixed-base scalar multiplication with
+/// `by` being in little-endian bit order. `by` must
+/// be a multiple of 3.
+pub fn fixed_base_multiplication<E, Var, CS>(
+    mut cs: CS,
+    base: FixedGenerators,
+    by: &[Boolean<Var>],
+    params: &E::Params
+) -> Result<EdwardsPoint<E, Var>, SynthesisError>
+    where CS: ConstraintSystem<E, Variable=Var>,
+          E: JubjubEngine,
+          Var: Copy
+{
+    // We're going to chunk the scalar into 3-bit windows,
+    // so let's force the caller to supply the right number
+    // of bits for our lookups.
+    assert!(by.len() % 3 == 0);
+
+    // Represents the result of the multiplication
+    let mut result = None;
+
+    for (i, (chunk, window)) in by.chunks(3)
+                                  .zip(params.circuit_generators(base).iter())
+                                  .enumerate()
+    {
+        let (x, y) = lookup3_xy(
+            cs.namespace(|| format!("window table lookup {}", i)),
+            chunk,
+            window
+        )?;
+
+        let p = EdwardsPoint {
+            x: x,
+            y: y
+        };
+
+        if result.is_none() {
+            result = Some(p);
+        } else {
+            result = Some(result.unwrap().add(
+                cs.namespace(|| format!("addition {}", i)),
+                &p,
+                params
+            )?);
+        }
+    }
+
+    Ok(result.get()?.clone())
+}
+
+/// Enforces that `bits`, in little-endian order, are a
+/// canonical encoding of an element of the Jubjub scalar
+/// field, i.e. that the value is less than the order of
+/// the prime order subgroup.
+pub fn enforce_in_scalar_field<E, Var, CS>(
+    mut cs: CS,
+    bits: &[Boolean<Var>]
+) -> Result<(), SynthesisError>
+    where CS: ConstraintSystem<E, Variable=Var>,
+          E: JubjubEngine,
+          Var: Copy
+{
+    let num_bits = E::Fs::NUM_BITS as usize;
+    assert!(bits.len() >= num_bits);
+
+    // Any bits beyond the size of the field must be zero.
+    for (i, bit) in bits[num_bits..].iter().enumerate() {
+        Boolean::enforce_equal(
+            cs.namespace(|| format!("high bit {} is zero", i)),
+            bit,
+            &Boolean::constant(false)
+        )?;
+    }
+
+    let mut be_bits = bits[0..num_bits].to_vec();
+    be_bits.reverse();
+
+    Boolean::enforce_in_field::<_, _, E::Fs>(
+        cs.namespace(|| "in scalar field"),
+        &be_bits
+    )
+}
+
+impl<E: JubjubEngine, Var: Copy> EdwardsPoint<E, Var> {
+    /// Multiplies the fixed generator `base` by a scalar
+    /// given in little-endian bit order. Unlike
+    /// `fixed_base_multiplication`, the scalar may be of
+    /// any length up to the size of the window tables.
+    pub fn fixed_base_mul<CS>(
+        cs: CS,
+        scalar_bits: &[Boolean<Var>],
+        base: FixedGenerators,
+        params: &E::Params
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        assert!(scalar_bits.len() <= params.circuit_generators(base).len() * 3);
+
+        // Pad the scalar with constant zero bits so that it
+        // fills out the last window; these cost nothing.
+        let mut bits = scalar_bits.to_vec();
+        while bits.len() % 3 != 0 {
+            bits.push(Boolean::constant(false));
+        }
+
+        fixed_base_multiplication(cs, base, &bits, params)
+    }
+
+    /// This extracts the x-coordinate, which is an injective
+    /// encoding for elements of the prime order subgroup.
+    pub fn into_num(&self) -> AllocatedNum<E, Var> {
+        self.x.clone()
+    }
+
+    /// Returns the bits of the y-coordinate in little-endian
+    /// order, followed by the sign (least significant bit) of
+    /// the x-coordinate. This matches `edwards::Point::write`.
+    pub fn into_bits<CS>(
+        &self,
+        mut cs: CS
+    ) -> Result<Vec<Boolean<Var>>, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        let mut bits = self.y.into_bits_strict(cs.namespace(|| "y"))?;
+        bits.reverse();
+
+        bits.push(self.x.lsb(cs.namespace(|| "x"))?);
+
+        Ok(bits)
+    }
+
+    /// Decodes a point from the 256 little-endian bits
+    /// produced by `into_bits`: the y-coordinate and then
+    /// the sign of x. The x-coordinate is witnessed and
+    /// constrained by the curve equation, and its sign
+    /// must match, so an encoding of x = 0 with the sign
+    /// bit set is rejected.
+    pub fn from_bits<CS>(
+        mut cs: CS,
+        bits: &[Boolean<Var>],
+        params: &E::Params
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        assert_eq!(bits.len(), E::Fr::NUM_BITS as usize + 1);
+
+        let mut y_bits = bits[0..(E::Fr::NUM_BITS as usize)].to_vec();
+        y_bits.reverse();
+        let sign = &bits[E::Fr::NUM_BITS as usize];
+
+        let y = AllocatedNum::from_bits_strict(cs.namespace(|| "y"), &y_bits)?;
+
+        let x = AllocatedNum::alloc(cs.namespace(|| "x"), || {
+            let y = *y.get_value().get()?;
+            let sign = *sign.get_value().get()?;
+
+            match edwards::Point::<E, _>::get_for_y(y, sign, params) {
+                Some(p) => Ok(p.into_xy().0),
+                None => Err(SynthesisError::AssignmentMissing)
+            }
+        })?;
+
+        let p = Self::interpret(cs.namespace(|| "interpretation"), &x, &y, params)?;
+
+        let x_sign = x.lsb(cs.namespace(|| "x sign"))?;
+        Boolean::enforce_equal(cs.namespace(|| "sign check"), &x_sign, sign)?;
+
+        Ok(p)
+    }
+
+    /// Enforces that this point is not the identity (0, 1)
+    /// by asserting that the x-coordinate is nonzero. This
+    /// also rules out the point of order two, (0, -1).
+    pub fn assert_not_identity<CS>(
+        &self,
+        cs: CS
+    ) -> Result<(), SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        self.x.assert_nonzero(cs)
+    }
+
+    /// Enforces that this point is in the prime order
+    /// subgroup. Every such point is a multiple of the
+    /// cofactor, so we witness q = [8^-1] self and check
+    /// that [8] q = self.
+    pub fn assert_in_subgroup<CS>(
+        &self,
+        mut cs: CS,
+        params: &E::Params
+    ) -> Result<(), SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        let q = match (self.x.get_value(), self.y.get_value()) {
+            (Some(x), Some(y)) => {
+                let sign = x.into_repr().is_odd();
+                let p = edwards::Point::<E, _>::get_for_y(y, sign, params);
+                let cofactor = E::Fs::from_str("8").unwrap();
+
+                // The constraints below can be satisfied only when
+                // the point is in the prime-order subgroup, so if it
+                // isn't even on the curve there is no witness to give.
+                match (p, cofactor.inverse()) {
+                    (Some(p), Some(inv)) => Some(p.mul(inv, params).into_xy()),
+                    _ => None
+                }
+            },
+            _ => None
+        };
+
+        let qx = AllocatedNum::alloc(cs.namespace(|| "q x"), || {
+            Ok(q.get()?.0)
+        })?;
+        let qy = AllocatedNum::alloc(cs.namespace(|| "q y"), || {
+            Ok(q.get()?.1)
+        })?;
+
+        let q = EdwardsPoint::interpret(
+            cs.namespace(|| "q interpretation"),
+            &qx,
+            &qy,
+            params
+        )?;
+
+        let q = q.double(cs.namespace(|| "first doubling"), params)?;
+        let q = q.double(cs.namespace(|| "second doubling"), params)?;
+        let q = q.double(cs.namespace(|| "third doubling"), params)?;
+
+        q.x.assert_equal(cs.namespace(|| "x equality"), &self.x)?;
+        q.y.assert_equal(cs.namespace(|| "y equality"), &self.y)?;
+
+        Ok(())
+    }
+
+    /// Returns `self` if condition is true, and the neutral
+    /// element (0, 1) otherwise.
+    pub fn conditionally_select<CS>(
+        &self,
+        mut cs: CS,
+        condition: &Boolean<Var>
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        // Compute x' = self.x if condition, and 0 otherwise
+        let x_prime = AllocatedNum::alloc(cs.namespace(|| "x'"), || {
+            if *condition.get_value().get()? {
+                Ok(*self.x.get_value().get()?)
+            } else {
+                Ok(E::Fr::zero())
+            }
+        })?;
+
+        // condition * x = x'
+        // if condition is 0, x' must be 0
+        // if condition is 1, x' must be x
+        let one = cs.one();
+        cs.enforce(
+            || "x' computation",
+            LinearCombination::<Var, E>::zero() + self.x.get_variable(),
+            condition.lc(one, E::Fr::one()),
+            LinearCombination::<Var, E>::zero() + x_prime.get_variable()
+        );
+
+        // Compute y' = self.y if condition, and 1 otherwise
+        let y_prime = AllocatedNum::alloc(cs.namespace(|| "y'"), || {
+            if *condition.get_value().get()? {
+                Ok(*self.y.get_value().get()?)
+            } else {
+                Ok(E::Fr::one())
+            }
+        })?;
+
+        // condition * y = y' - (1 - condition)
+        // if condition is 0, y' must be 1
+        // if condition is 1, y' must be y
+        cs.enforce(
+            || "y' computation",
+            LinearCombination::<Var, E>::zero() + self.y.get_variable(),
+            condition.lc(one, E::Fr::one()),
+            LinearCombination::<Var, E>::zero() + y_prime.get_variable()
+                                                - &condition.not().lc(one, E::Fr::one())
+        );
+
+        Ok(EdwardsPoint {
+            x: x_prime,
+            y: y_prime
+        })
+    }
+
+    /// Takes two points (a, b) and returns a if the
+    /// condition is true, and b otherwise. Constant
+    /// conditions do not add any constraints.
+    pub fn select<CS>(
+        mut cs: CS,
+        a: &Self,
+        b: &Self,
+        condition: &Boolean<Var>
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        let x = AllocatedNum::conditionally_select(
+            cs.namespace(|| "x"),
+            &a.x,
+            &b.x,
+            condition
+        )?;
+
+        let y = AllocatedNum::conditionally_select(
+            cs.namespace(|| "y"),
+            &a.y,
+            &b.y,
+            condition
+        )?;
+
+        Ok(EdwardsPoint {
+            x: x,
+            y: y
+        })
+    }
+
+    /// Takes two points (a, b) and returns (b, a) if
+    /// the condition is true, and (a, b) otherwise.
+    pub fn conditionally_reverse<CS>(
+        mut cs: CS,
+        a: &Self,
+        b: &Self,
+        condition: &Boolean<Var>
+    ) -> Result<(Self, Self), SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        let (x0, x1) = AllocatedNum::conditionally_reverse(
+            cs.namespace(|| "x"),
+            &a.x,
+            &b.x,
+            condition
+        )?;
+
+        let (y0, y1) = AllocatedNum::conditionally_reverse(
+            cs.namespace(|| "y"),
+            &a.y,
+            &b.y,
+            condition
+        )?;
+
+        Ok((
+            EdwardsPoint {
+                x: x0,
+                y: y0
+            },
+            EdwardsPoint {
+                x: x1,
+                y: y1
+            }
+        ))
+    }
+
+    /// Performs a scalar multiplication of this twisted Edwards
+    /// point by a scalar represented as a sequence of booleans
+    /// in little-endian bit order.
+    pub fn mul<CS>(
+        &self,
+        mut cs: CS,
+        by: &[Boolean<Var>],
+        params: &E::Params
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        // Represents the current "magnitude" of the base
+        // that we're operating over. Starts at self,
+        // then 2*self, then 4*self, ...
+        let mut curbase = None;
+
+        // Represents the result of the multiplication
+        let mut result = None;
+
+        for (i, bit) in by.iter().enumerate() {
+            if curbase.is_none() {
+                curbase = Some(self.clone());
+            } else {
+                // Double the previous value
+                curbase = Some(
+                    curbase.unwrap()
+                           .double(cs.namespace(|| format!("doubling {}", i)), params)?
+                );
+            }
+
+            // Represents the select base. If the bit for this magnitude
+            // is true, this will return `curbase`. Otherwise it will
+            // return the neutral element, which will have no effect on
+            // the result.
+            let thisbase = curbase.as_ref()
+                                  .unwrap()
+                                  .conditionally_select(
+                                      cs.namespace(|| format!("selection {}", i)),
+                                      bit
+                                  )?;
+
+            if result.is_none() {
+                result = Some(thisbase);
+            } else {
+                result = Some(result.unwrap().add(
+                    cs.namespace(|| format!("addition {}", i)),
+                    &thisbase,
+                    params
+                )?);
+            }
+        }
+
+        Ok(result.get()?.clone())
+    }
+
+    /// Performs a scalar multiplication of this twisted Edwards
+    /// point by a scalar represented as a sequence of booleans
+    /// in big-endian bit order, using a double-and-add ladder.
+    pub fn mul_msb_first<CS>(
+        &self,
+        mut cs: CS,
+        by: &[Boolean<Var>],
+        params: &E::Params
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        // Represents the result of the multiplication
+        let mut result: Option<Self> = None;
+
+        for (i, bit) in by.iter().enumerate() {
+            // Select either this point or the neutral element,
+            // depending on the value of the bit.
+            let thisbase = self.conditionally_select(
+                cs.namespace(|| format!("selection {}", i)),
+                bit
+            )?;
+
+            result = Some(match result {
+                None => thisbase,
+                Some(result) => {
+                    result.double(
+                        cs.namespace(|| format!("doubling {}", i)),
+                        params
+                    )?.add(
+                        cs.namespace(|| format!("addition {}", i)),
+                        &thisbase,
+                        params
+                    )?
+                }
+            });
+        }
+
+        Ok(result.get()?.clone())
+    }
+
+    /// Computes `sum_i [scalars[i]] bases[i]`, with each
+    /// scalar in little-endian bit order. The bits of all
+    /// the scalars are processed together from the most
+    /// significant down, so the doublings are shared.
+    pub fn multi_mul<CS>(
+        mut cs: CS,
+        scalars: &[Vec<Boolean<Var>>],
+        bases: &[Self],
+        params: &E::Params
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        assert_eq!(scalars.len(), bases.len());
+
+        let num_bits = scalars.iter().map(|s| s.len()).max().unwrap_or(0);
+
+        // Represents the result of the multiplication
+        let mut result: Option<Self> = None;
+
+        for j in (0..num_bits).rev() {
+            let mut cs = cs.namespace(|| format!("bit {}", j));
+
+            if let Some(r) = result {
+                result = Some(r.double(cs.namespace(|| "doubling"), params)?);
+            }
+
+            for (i, (scalar, base)) in scalars.iter().zip(bases.iter()).enumerate() {
+                // Shorter scalars only join in at their
+                // most significant bit.
+                if j >= scalar.len() {
+                    continue;
+                }
+
+                let thisbase = base.conditionally_select(
+                    cs.namespace(|| format!("selection {}", i)),
+                    &scalar[j]
+                )?;
+
+                result = Some(match result {
+                    None => thisbase,
+                    Some(result) => result.add(
+                        cs.namespace(|| format!("addition {}", i)),
+                        &thisbase,
+                        params
+                    )?
+                });
+            }
+        }
+
+        Ok(result.get()?.clone())
+    }
+
+    /// Interprets an (x, y) pair as a twisted Edwards
+    /// point, enforcing that it lies on the curve.
+    /// Does not check that it's in the prime order
+    /// subgroup.
+    pub fn interpret<CS>(
+        mut cs: CS,
+        x: &AllocatedNum<E, Var>,
+        y: &AllocatedNum<E, Var>,
+        params: &E::Params
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        // -x^2 + y^2 = 1 + dx^2y^2
+
+        let x2 = x.square(cs.namespace(|| "x^2"))?;
+        let y2 = y.square(cs.namespace(|| "y^2"))?;
+        let x2y2 = x2.mul(cs.namespace(|| "x^2 y^2"), &y2)?;
+
+        let one = cs.one();
+        cs.enforce(
+            || "on curve check",
+            LinearCombination::zero() - x2.get_variable()
+                                      + y2.get_variable(),
+            LinearCombination::zero() + one,
+            LinearCombination::zero() + one
+                                      + (*params.edwards_d(), x2y2.get_variable())
+        );
+
+        Ok(EdwardsPoint {
+            x: x.clone(),
+            y: y.clone()
+        })
+    }
+
+    /// Performs a point doubling, specializing the addition
+    /// formula so that it costs one fewer constraint.
+    pub fn double<CS>(
+        &self,
+        mut cs: CS,
+        params: &E::Params
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        // Compute T = (x1 + y1) * (x1 + y1)
+        let t = AllocatedNum::alloc(cs.namespace(|| "T"), || {
+            let mut t0 = *self.x.get_value().get()?;
+            t0.add_assign(self.y.get_value().get()?);
+            t0.square();
+
+            Ok(t0)
+        })?;
+
+        cs.enforce(
+            || "T computation",
+            LinearCombination::<Var, E>::zero() + self.x.get_variable()
+                                                + self.y.get_variable(),
+            LinearCombination::<Var, E>::zero() + self.x.get_variable()
+                                                + self.y.get_variable(),
+            LinearCombination::<Var, E>::zero() + t.get_variable()
+        );
+
+        // Compute A = x1 * y1
+        let a = self.x.mul(cs.namespace(|| "A computation"), &self.y)?;
+
+        // Compute C = d*A*A
+        let c = AllocatedNum::alloc(cs.namespace(|| "C"), || {
+            let mut t0 = *a.get_value().get()?;
+            t0.square();
+            t0.mul_assign(params.edwards_d());
+
+            Ok(t0)
+        })?;
+
+        cs.enforce(
+            || "C computation",
+            LinearCombination::<Var, E>::zero() + (*params.edwards_d(), a.get_variable()),
+            LinearCombination::<Var, E>::zero() + a.get_variable(),
+            LinearCombination::<Var, E>::zero() + c.get_variable()
+        );
+
+        // Compute x3 = (2.A) / (1 + C)
+        let x3 = AllocatedNum::alloc(cs.namespace(|| "x3"), || {
+            let mut t0 = *a.get_value().get()?;
+            t0.double();
+
+            let mut t1 = E::Fr::one();
+            t1.add_assign(c.get_value().get()?);
+
+            match t1.inverse() {
+                Some(t1) => {
+                    t0.mul_assign(&t1);
+
+                    Ok(t0)
+                },
+                None => {
+                    Err(SynthesisError::AssignmentMissing)
+                }
+            }
+        })?;
+
+        let one = cs.one();
+        cs.enforce(
+            || "x3 computation",
+            LinearCombination::<Var, E>::zero() + one + c.get_variable(),
+            LinearCombination::<Var, E>::zero() + x3.get_variable(),
+            LinearCombination::<Var, E>::zero() + a.get_variable()
+                                                + a.get_variable()
+        );
+
+        // Compute y3 = (T - 2.A) / (1 - C)
+        let y3 = AllocatedNum::alloc(cs.namespace(|| "y3"), || {
+            let mut t0 = *t.get_value().get()?;
+            t0.sub_assign(a.get_value().get()?);
+            t0.sub_assign(a.get_value().get()?);
+
+            let mut t1 = E::Fr::one();
+            t1.sub_assign(c.get_value().get()?);
+
+            match t1.inverse() {
+                Some(t1) => {
+                    t0.mul_assign(&t1);
+
+                    Ok(t0)
+                },
+                None => {
+                    Err(SynthesisError::AssignmentMissing)
+                }
+            }
+        })?;
+
+        cs.enforce(
+            || "y3 computation",
+            LinearCombination::<Var, E>::zero() + one - c.get_variable(),
+            LinearCombination::<Var, E>::zero() + y3.get_variable(),
+            LinearCombination::<Var, E>::zero() + t.get_variable()
+                                                - a.get_variable()
+                                                - a.get_variable()
+        );
+
+        Ok(EdwardsPoint {
+            x: x3,
+            y: y3
+        })
+    }
+
+    /// Perform addition between any two points
+    pub fn add<CS>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+        params: &E::Params
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        // Compute U = (x1 + y1) * (x2 + y2)
+        let u = AllocatedNum::alloc(cs.namespace(|| "U"), || {
+            let mut t0 = *self.x.get_value().get()?;
+            t0.add_assign(self.y.get_value().get()?);
+
+            let mut t1 = *other.x.get_value().get()?;
+            t1.add_assign(other.y.get_value().get()?);
+
+            t0.mul_assign(&t1);
+
+            Ok(t0)
+        })?;
+
+        cs.enforce(
+            || "U computation",
+            LinearCombination::<Var, E>::zero() + self.x.get_variable()
+                                                + self.y.get_variable(),
+            LinearCombination::<Var, E>::zero() + other.x.get_variable()
+                                                + other.y.get_variable(),
+            LinearCombination::<Var, E>::zero() + u.get_variable()
+        );
+
+        // Compute A = y2 * x1
+        let a = other.y.mul(cs.namespace(|| "A computation"), &self.x)?;
+
+        // Compute B = x2 * y1
+        let b = other.x.mul(cs.namespace(|| "B computation"), &self.y)?;
+
+        // Compute C = d*A*B
+        let c = AllocatedNum::alloc(cs.namespace(|| "C"), || {
+            let mut t0 = *a.get_value().get()?;
+            t0.mul_assign(b.get_value().get()?);
+            t0.mul_assign(params.edwards_d());
+
+            Ok(t0)
+        })?;
+
+        cs.enforce(
+            || "C computation",
+            LinearCombination::<Var, E>::zero() + (*params.edwards_d(), a.get_variable()),
+            LinearCombination::<Var, E>::zero() + b.get_variable(),
+            LinearCombination::<Var, E>::zero() + c.get_variable()
+        );
+
+        // Compute x3 = (A + B) / (1 + C)
+        let x3 = AllocatedNum::alloc(cs.namespace(|| "x3"), || {
+            let mut t0 = *a.get_value().get()?;
+            t0.add_assign(b.get_value().get()?);
+
+            let mut t1 = E::Fr::one();
+            t1.add_assign(c.get_value().get()?);
+
+            match t1.inverse() {
+                Some(t1) => {
+                    t0.mul_assign(&t1);
+
+                    Ok(t0)
+                },
+                None => {
+                    Err(SynthesisError::AssignmentMissing)
+                }
+            }
+        })?;
+
+        let one = cs.one();
+        cs.enforce(
+            || "x3 computation",
+            LinearCombination::<Var, E>::zero() + one + c.get_variable(),
+            LinearCombination::<Var, E>::zero() + x3.get_variable(),
+            LinearCombination::<Var, E>::zero() + a.get_variable()
+                                                + b.get_variable()
+        );
+
+        // Compute y3 = (U - A - B) / (1 - C)
+        let y3 = AllocatedNum::alloc(cs.namespace(|| "y3"), || {
+            let mut t0 = *u.get_value().get()?;
+            t0.sub_assign(a.get_value().get()?);
+            t0.sub_assign(b.get_value().get()?);
+
+            let mut t1 = E::Fr::one();
+            t1.sub_assign(c.get_value().get()?);
+
+            match t1.inverse() {
+                Some(t1) => {
+                    t0.mul_assign(&t1);
+
+                    Ok(t0)
+                },
+                None => {
+                    Err(SynthesisError::AssignmentMissing)
+                }
+            }
+        })?;
+
+        cs.enforce(
+            || "y3 computation",
+            LinearCombination::<Var, E>::zero() + one - c.get_variable(),
+            LinearCombination::<Var, E>::zero() + y3.get_variable(),
+            LinearCombination::<Var, E>::zero() + u.get_variable()
+                                                - a.get_variable()
+                                                - b.get_variable()
+        );
+
+        Ok(EdwardsPoint {
+            x: x3,
+            y: y3
+        })
+    }
+
+    /// Adds a constant point to this one. Folding the
+    /// constant's coordinates into the constraints makes
+    /// this cost three constraints rather than six.
+    pub fn add_constant<CS>(
+        &self,
+        mut cs: CS,
+        constant: &edwards::Point<E, PrimeOrder>,
+        params: &E::Params
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        let (cx, cy) = constant.into_xy();
+
+        // A = cy * x1 and B = cx * y1 are linear.
+
+        // Compute C = d*A*B = (d*cx*cy) * x1 * y1
+        let mut dcxcy = *params.edwards_d();
+        dcxcy.mul_assign(&cx);
+        dcxcy.mul_assign(&cy);
+
+        let c = AllocatedNum::alloc(cs.namespace(|| "C"), || {
+            let mut t0 = *self.x.get_value().get()?;
+            t0.mul_assign(self.y.get_value().get()?);
+            t0.mul_assign(&dcxcy);
+
+            Ok(t0)
+        })?;
+
+        cs.enforce(
+            || "C computation",
+            LinearCombination::<Var, E>::zero() + (dcxcy, self.x.get_variable()),
+            LinearCombination::<Var, E>::zero() + self.y.get_variable(),
+            LinearCombination::<Var, E>::zero() + c.get_variable()
+        );
+
+        // Compute x3 = (A + B) / (1 + C)
+        let x3 = AllocatedNum::alloc(cs.namespace(|| "x3"), || {
+            let mut t0 = *self.x.get_value().get()?;
+            t0.mul_assign(&cy);
+            let mut t1 = *self.y.get_value().get()?;
+            t1.mul_assign(&cx);
+            t0.add_assign(&t1);
+
+            let mut t1 = E::Fr::one();
+            t1.add_assign(c.get_value().get()?);
+
+            match t1.inverse() {
+                Some(t1) => {
+                    t0.mul_assign(&t1);
+
+                    Ok(t0)
+                },
+                None => {
+                    Err(SynthesisError::AssignmentMissing)
+                }
+            }
+        })?;
+
+        let one = cs.one();
+        cs.enforce(
+            || "x3 computation",
+            LinearCombination::<Var, E>::zero() + one + c.get_variable(),
+            LinearCombination::<Var, E>::zero() + x3.get_variable(),
+            LinearCombination::<Var, E>::zero() + (cy, self.x.get_variable())
+                                                + (cx, self.y.get_variable())
+        );
+
+        // Compute y3 = (U - A - B) / (1 - C)
+        //            = (cx * x1 + cy * y1) / (1 - C)
+        let y3 = AllocatedNum::alloc(cs.namespace(|| "y3"), || {
+            let mut t0 = *self.x.get_value().get()?;
+            t0.mul_assign(&cx);
+            let mut t1 = *self.y.get_value().get()?;
+            t1.mul_assign(&cy);
+            t0.add_assign(&t1);
+
+            let mut t1 = E::Fr::one();
+            t1.sub_assign(c.get_value().get()?);
+
+            match t1.inverse() {
+                Some(t1) => {
+                    t0.mul_assign(&t1);
+
+                    Ok(t0)
+                },
+                None => {
+                    Err(SynthesisError::AssignmentMissing)
+                }
+            }
+        })?;
+
+        cs.enforce(
+            || "y3 computation",
+            LinearCombination::<Var, E>::zero() + one - c.get_variable(),
+            LinearCombination::<Var, E>::zero() + y3.get_variable(),
+            LinearCombination::<Var, E>::zero() + (cx, self.x.get_variable())
+                                                + (cy, self.y.get_variable())
+        );
+
+        Ok(EdwardsPoint {
+            x: x3,
+            y: y3
+        })
+    }
+}
+
+/// The 256-bit encoding of a native point, as
+/// written by `edwards::Point::write`, for use as
+/// a witness.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompressedEdwards {
+    bits: Vec<bool>
+}
+
+impl CompressedEdwards {
+    pub fn from_native<E: JubjubEngine, Subgroup>(
+        p: &edwards::Point<E, Subgroup>
+    ) -> Self
+    {
+        let mut bytes = vec![];
+        p.write(&mut bytes).unwrap();
+
+        CompressedEdwards {
+            bits: bytes.iter()
+                       .flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1))
+                       .collect()
+        }
+    }
+
+    /// The encoding in little-endian bit order, as
+    /// produced by `EdwardsPoint::into_bits`.
+    pub fn get_bits(&self) -> &[bool] {
+        &self.bits
+    }
+
+    /// Witnesses the bits of the encoding and decodes
+    /// them with `EdwardsPoint::from_bits`.
+    pub fn witness_into_circuit<E, CS, Var: Copy>(
+        &self,
+        mut cs: CS,
+        params: &E::Params
+    ) -> Result<EdwardsPoint<E, Var>, SynthesisError>
+        where E: JubjubEngine, CS: ConstraintSystem<E, Variable=Var>
+    {
+        let mut bits = Vec::with_capacity(self.bits.len());
+        for (i, b) in self.bits.iter().enumerate() {
+            bits.push(Boolean::from(AllocatedBit::alloc(
+                cs.namespace(|| format!("bit {}", i)),
+                Some(*b)
+            )?));
+        }
+
+        EdwardsPoint::from_bits(cs.namespace(|| "decompression"), &bits, params)
+    }
+}
+
+impl<E: JubjubEngine, Var: Copy> AllocatedNum<E, Var> {
+    /// Returns whether this number is the x-coordinate of
+    /// some point on the curve, i.e. whether
+    /// (1 + x^2) / (1 - d x^2) is a square. The prover
+    /// witnesses the answer along with a square root of
+    /// either that value or its product with a fixed
+    /// nonresidue, so this costs five constraints.
+    ///
+    /// For the two x with 1 + x^2 = 0, which are valid
+    /// with y = 0, either answer can be proven.
+    pub fn is_valid_jubjub_x<CS>(
+        &self,
+        mut cs: CS,
+        params: &E::Params
+    ) -> Result<Boolean<Var>, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        let x2 = self.square(cs.namespace(|| "x^2"))?;
+
+        let nonresidue = E::Fr::multiplicative_generator();
+
+        // The denominator is never zero, as d is
+        // not a square.
+        let u = x2.get_value().map(|x2| {
+            let mut num = x2;
+            num.add_assign(&E::Fr::one());
+
+            let mut den = x2;
+            den.mul_assign(params.edwards_d());
+            den.negate();
+            den.add_assign(&E::Fr::one());
+
+            num.mul_assign(&den.inverse().unwrap());
+            num
+        });
+
+        let is_valid = AllocatedBit::alloc(
+            cs.namespace(|| "is valid"),
+            u.map(|u| u.legendre() != LegendreSymbol::QuadraticNonResidue)
+        )?;
+
+        let root = AllocatedNum::alloc(cs.namespace(|| "root"), || {
+            let mut u = *u.get()?;
+            if u.legendre() == LegendreSymbol::QuadraticNonResidue {
+                u.mul_assign(&nonresidue);
+            }
+
+            Ok(u.sqrt().unwrap())
+        })?;
+        let root2 = root.square(cs.namespace(|| "root^2"))?;
+
+        // t = (1 + x^2) * (nonresidue + (1 - nonresidue) * is_valid)
+        let t = AllocatedNum::alloc(cs.namespace(|| "t"), || {
+            let mut t = *x2.get_value().get()?;
+            t.add_assign(&E::Fr::one());
+
+            if !*is_valid.get_value().get()? {
+                t.mul_assign(&nonresidue);
+            }
+
+            Ok(t)
+        })?;
+
+        let mut coeff = E::Fr::one();
+        coeff.sub_assign(&nonresidue);
+
+        let one = cs.one();
+        cs.enforce(
+            || "t computation",
+            LinearCombination::<Var, E>::zero() + one + x2.get_variable(),
+            LinearCombination::<Var, E>::zero() + (nonresidue, one)
+                                                + (coeff, is_valid.get_variable()),
+            LinearCombination::<Var, E>::zero() + t.get_variable()
+        );
+
+        // root^2 * (1 - d x^2) = t
+        cs.enforce(
+            || "root check",
+            LinearCombination::<Var, E>::zero() + root2.get_variable(),
+            LinearCombination::<Var, E>::zero() + one
+                                                - (*params.edwards_d(), x2.get_variable()),
+            LinearCombination::<Var, E>::zero() + t.get_variable()
+        );
+
+        Ok(Boolean::from(is_valid))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bellman::{ConstraintSystem};
+    use rand::{XorShiftRng, SeedableRng, Rand, Rng};
+    use pairing::bls12_381::{Bls12, Fr};
+    use pairing::{BitIterator, Field, SqrtField, PrimeField, PrimeFieldRepr};
+    use ::circuit::test::*;
+    use ::jubjub::{
+        edwards,
+        JubjubBls12,
+        JubjubParams,
+        FixedGenerators,
+        PrimeOrder,
+        Unknown
+    };
+    use ::jubjub::fs::Fs;
+    use super::{
+        EdwardsPoint,
+        CompressedEdwards,
+        AllocatedNum,
+        fixed_base_multiplication,
+        enforce_in_scalar_field
+    };
+    use ::circuit::boolean::{
+        Boolean,
+        AllocatedBit
+    };
+
+    #[test]
+    fn test_interpret() {
+        let params = &JubjubBls12::new();
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..100 {
+            let p = edwards::Point::<Bls12, _>::rand(rng, &params);
+            let (x, y) = p.into_xy();
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+            let numx = AllocatedNum::alloc(cs.namespace(|| "x"), || {
+                Ok(x)
+            }).unwrap();
+            let numy = AllocatedNum::alloc(cs.namespace(|| "y"), || {
+                Ok(y)
+            }).unwrap();
+
+            let p = EdwardsPoint::interpret(&mut cs, &numx, &numy, &params).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(p.x.get_value().unwrap(), x);
+            assert_eq!(p.y.get_value().unwrap(), y);
+        }
+
+        // Random (x, y) are unlikely to be on the curve.
+        for _ in 0..100 {
+            let x = rng.gen();
+            let y = rng.gen();
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+            let numx = AllocatedNum::alloc(cs.namespace(|| "x"), || {
+                Ok(x)
+            }).unwrap();
+            let numy = AllocatedNum::alloc(cs.namespace(|| "y"), || {
+                Ok(y)
+            }).unwrap();
+
+            EdwardsPoint::interpret(&mut cs, &numx, &numy, &params).unwrap();
+
+            assert_eq!(cs.which_is_unsatisfied().unwrap(), "on curve check");
+        }
+
+        // Perturbing y of a valid point takes it off the curve.
+        for _ in 0..100 {
+            let p = edwards::Point::<Bls12, _>::rand(rng, &params);
+            let (x, mut y) = p.into_xy();
+            y.add_assign(&Fr::one());
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+            let numx = AllocatedNum::alloc(cs.namespace(|| "x"), || {
+                Ok(x)
+            }).unwrap();
+            let numy = AllocatedNum::alloc(cs.namespace(|| "y"), || {
+                Ok(y)
+            }).unwrap();
+
+            EdwardsPoint::interpret(&mut cs, &numx, &numy, &params).unwrap();
+
+            assert_eq!(cs.which_is_unsatisfied().unwrap(), "on curve check");
+        }
+    }
+
+    #[test]
+    fn test_is_valid_jubjub_x() {
+        let params = &JubjubBls12::new();
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        let mut num_invalid = 0;
+        for i in 0..100 {
+            let (x, expected) = if i % 2 == 0 {
+                (edwards::Point::<Bls12, _>::rand(rng, params).into_xy().0, true)
+            } else {
+                // Compute the expected answer natively.
+                let x: Fr = rng.gen();
+                let mut num = x;
+                num.square();
+                let mut den = num;
+                num.add_assign(&Fr::one());
+                den.mul_assign(params.edwards_d());
+                den.negate();
+                den.add_assign(&Fr::one());
+                num.mul_assign(&den.inverse().unwrap());
+
+                (x, num.sqrt().is_some())
+            };
+            if !expected {
+                num_invalid += 1;
+            }
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+            let numx = AllocatedNum::alloc(cs.namespace(|| "x"), || {
+                Ok(x)
+            }).unwrap();
+
+            let is_valid = numx.is_valid_jubjub_x(cs.namespace(|| "check"), params).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(cs.num_constraints(), 5);
+            assert_eq!(is_valid.get_value().unwrap(), expected);
+
+            // The prover cannot claim the opposite.
+            cs.set("check/is valid/boolean", if expected { Fr::zero() } else { Fr::one() });
+            assert!(!cs.is_satisfied());
+        }
+
+        assert!(num_invalid > 0);
+    }
+
+    #[test]
+    fn test_assert_in_subgroup() {
+        let params = &JubjubBls12::new();
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for i in 0..100 {
+            let p = edwards::Point::<Bls12, _>::rand(rng, &params);
+
+            // Alternate between a prime order point and a
+            // point of small order.
+            let (p, in_subgroup) = if i % 2 == 0 {
+                (edwards::Point::from(p.mul_by_cofactor(params)), true)
+            } else {
+                let p = p.mul(Fs::char(), params);
+                if p == edwards::Point::zero() {
+                    continue;
+                }
+                (p, false)
+            };
+
+            let (x, y) = p.into_xy();
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+            let numx = AllocatedNum::alloc(cs.namespace(|| "x"), || {
+                Ok(x)
+            }).unwrap();
+            let numy = AllocatedNum::alloc(cs.namespace(|| "y"), || {
+                Ok(y)
+            }).unwrap();
+
+            let p = EdwardsPoint::interpret(&mut cs, &numx, &numy, &params).unwrap();
+            p.assert_in_subgroup(cs.namespace(|| "subgroup check"), params).unwrap();
+
+            assert_eq!(cs.num_constraints(), 4 + 21);
+
+            if in_subgroup {
+                assert!(cs.is_satisfied());
+            } else {
+                assert!(cs.which_is_unsatisfied().unwrap().starts_with("subgroup check/"));
+            }
+        }
+    }
+
+    #[test]
+    fn test_into_bits() {
+        let params = &JubjubBls12::new();
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..100 {
+            let p = edwards::Point::<Bls12, _>::rand(rng, &params);
+            let (x, y) = p.into_xy();
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+            let numx = AllocatedNum::alloc(cs.namespace(|| "x"), || {
+                Ok(x)
+            }).unwrap();
+            let numy = AllocatedNum::alloc(cs.namespace(|| "y"), || {
+                Ok(y)
+            }).unwrap();
+
+            let q = EdwardsPoint {
+                x: numx,
+                y: numy
+            };
+
+            let bits = q.into_bits(cs.namespace(|| "into bits")).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(bits.len(), 256);
+
+            let mut bytes = vec![];
+            p.write(&mut bytes).unwrap();
+
+            let expected = bytes.iter()
+                                .flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1))
+                                .collect::<Vec<_>>();
+
+            let bits = bits.iter().map(|b| b.get_value().unwrap()).collect::<Vec<_>>();
+
+            assert_eq!(bits, expected);
+        }
+    }
+
+    #[test]
+    fn test_from_bits() {
+        let params = &JubjubBls12::new();
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        fn decode(
+            bits: &[bool],
+            params: &JubjubBls12
+        ) -> (TestConstraintSystem<Bls12>, Option<(Fr, Fr)>)
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let bits = bits.iter().enumerate().map(|(i, b)| {
+                Boolean::from(
+                    AllocatedBit::alloc(cs.namespace(|| format!("bit {}", i)), Some(*b)).unwrap()
+                )
+            }).collect::<Vec<_>>();
+
+            let p = EdwardsPoint::from_bits(cs.namespace(|| "from bits"), &bits, params)
+                                 .ok()
+                                 .map(|p| (p.x.get_value().unwrap(), p.y.get_value().unwrap()));
+
+            (cs, p)
+        }
+
+        fn encoding(p: &edwards::Point<Bls12, Unknown>) -> Vec<bool> {
+            let mut bytes = vec![];
+            p.write(&mut bytes).unwrap();
+
+            bytes.iter()
+                 .flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1))
+                 .collect()
+        }
+
+        for _ in 0..20 {
+            let p = edwards::Point::<Bls12, _>::rand(rng, &params);
+
+            let (cs, q) = decode(&encoding(&p), params);
+
+            assert!(cs.is_satisfied());
+            assert_eq!(q.unwrap(), p.into_xy());
+
+            // Flipping the sign bit gives the negation.
+            let mut bits = encoding(&p);
+            bits[255] = !bits[255];
+
+            let (cs, q) = decode(&bits, params);
+
+            assert!(cs.is_satisfied());
+            assert_eq!(q.unwrap(), p.negate().into_xy());
+        }
+
+        // The identity (0, 1) and the point of order two
+        // (0, -1) have x = 0, which must be encoded with
+        // the sign bit clear.
+        let mut minus_one = Fr::one();
+        minus_one.negate();
+
+        for y in vec![Fr::one(), minus_one] {
+            let p = edwards::Point::<Bls12, _>::get_for_y(y, false, params).unwrap();
+            assert!(p.into_xy().0.is_zero());
+
+            let (cs, q) = decode(&encoding(&p), params);
+
+            assert!(cs.is_satisfied());
+            assert_eq!(q.unwrap(), (Fr::zero(), y));
+
+            let mut bits = encoding(&p);
+            bits[255] = true;
+
+            let (cs, _) = decode(&bits, params);
+
+            assert_eq!(cs.which_is_unsatisfied(), Some("from bits/sign check/equality constraint"));
+        }
+    }
+
+    #[test]
+    fn test_assert_not_identity() {
+        let params = &JubjubBls12::new();
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..100 {
+            let p = edwards::Point::<Bls12, _>::rand(rng, &params);
+            let (x, y) = p.into_xy();
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+            let numx = AllocatedNum::alloc(cs.namespace(|| "x"), || {
+                Ok(x)
+            }).unwrap();
+            let numy = AllocatedNum::alloc(cs.namespace(|| "y"), || {
+                Ok(y)
+            }).unwrap();
+
+            let p = EdwardsPoint {
+                x: numx,
+                y: numy
+            };
+            p.assert_not_identity(cs.namespace(|| "not identity")).unwrap();
+
+            assert!(cs.is_satisfied());
+
+            cs.set("x/num", Fr::zero());
+            assert_eq!(cs.which_is_unsatisfied().unwrap(), "not identity/nonzero assertion constraint");
+        }
+
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+            let numx = AllocatedNum::alloc(cs.namespace(|| "x"), || {
+                Ok(Fr::zero())
+            }).unwrap();
+            let numy = AllocatedNum::alloc(cs.namespace(|| "y"), || {
+                Ok(Fr::one())
+            }).unwrap();
+
+            let p = EdwardsPoint {
+                x: numx,
+                y: numy
+            };
+
+            assert!(p.assert_not_identity(cs.namespace(|| "not identity")).is_err());
+        }
+    }
+
+    #[test]
+    fn test_edwards_fixed_base_multiplication()  {
+        let params = &JubjubBls12::new();
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..100 {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let p = params.generator(FixedGenerators::NoteCommitmentRandomization);
+            let s = Fs::rand(rng);
+            let q = p.mul(s, params);
+            let (x1, y1) = q.into_xy();
+
+            let mut s_bits = BitIterator::new(s.into_repr()).collect::<Vec<_>>();
+            s_bits.reverse();
+            s_bits.truncate(Fs::NUM_BITS as usize);
+
+            let s_bits = s_bits.into_iter()
+                               .enumerate()
+                               .map(|(i, b)| AllocatedBit::alloc(cs.namespace(|| format!("scalar bit {}", i)), Some(b)).unwrap())
+                               .map(|v| Boolean::from(v))
+                               .collect::<Vec<_>>();
+
+            let q = fixed_base_multiplication(
+                cs.namespace(|| "multiplication"),
+                FixedGenerators::NoteCommitmentRandomization,
+                &s_bits,
+                params
+            ).unwrap();
+
+            assert_eq!(q.x.get_value().unwrap(), x1);
+            assert_eq!(q.y.get_value().unwrap(), y1);
+        }
+    }
+
+    #[test]
+    fn test_enforce_in_scalar_field() {
+        // Returns the 256 little-endian bits of r + offset
+        fn bits_of(offset: u64) -> Vec<bool> {
+            let mut repr = Fs::char();
+            if offset == 0 {
+                repr.sub_noborrow(&1.into());
+            } else {
+                repr.add_nocarry(&(offset - 1).into());
+            }
+
+            let mut bits = BitIterator::new(repr).collect::<Vec<_>>();
+            bits.reverse();
+
+            bits
+        }
+
+        // r - 1, r and r + 1
+        for (offset, valid) in vec![(0, true), (1, false), (2, false)] {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let bits = bits_of(offset).into_iter()
+                                      .enumerate()
+                                      .map(|(i, b)| Boolean::from(AllocatedBit::alloc(cs.namespace(|| format!("bit {}", i)), Some(b)).unwrap()))
+                                      .collect::<Vec<_>>();
+
+            assert_eq!(bits.len(), 256);
+
+            enforce_in_scalar_field(cs.namespace(|| "range check"), &bits).unwrap();
+
+            assert_eq!(cs.is_satisfied(), valid);
+        }
+
+        // A set bit above the size of the field is rejected.
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+        let mut bits = vec![Boolean::constant(false); Fs::NUM_BITS as usize];
+        bits.push(Boolean::from(AllocatedBit::alloc(cs.namespace(|| "high bit"), Some(true)).unwrap()));
+
+        enforce_in_scalar_field(cs.namespace(|| "range check"), &bits).unwrap();
+
+        assert_eq!(cs.which_is_unsatisfied(), Some("range check/high bit 0 is zero/equality constraint"));
+    }
+
+    #[test]
+    fn test_edwards_fixed_base_mul()  {
+        let params = &JubjubBls12::new();
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..100 {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let p = params.generator(FixedGenerators::NoteCommitmentRandomization);
+            let s = Fs::rand(rng);
+
+            // Use a scalar length which is not a multiple of 3.
+            let num_bits = rng.gen_range(1, Fs::NUM_BITS as usize + 1);
+
+            let mut s_bits = BitIterator::new(s.into_repr()).collect::<Vec<_>>();
+            s_bits.reverse();
+            s_bits.truncate(num_bits);
+
+            let mut truncated = s_bits.clone();
+            truncated.reverse();
+            let mut scalar = Fs::zero();
+            for b in truncated {
+                scalar.double();
+                if b {
+                    scalar.add_assign(&Fs::one());
+                }
+            }
+
+            let q = p.mul(scalar, params);
+            let (x1, y1) = q.into_xy();
+
+            let s_bits = s_bits.into_iter()
+                               .enumerate()
+                               .map(|(i, b)| AllocatedBit::alloc(cs.namespace(|| format!("scalar bit {}", i)), Some(b)).unwrap())
+                               .map(|v| Boolean::from(v))
+                               .collect::<Vec<_>>();
+
+            let q = EdwardsPoint::fixed_base_mul(
+                cs.namespace(|| "multiplication"),
+                &s_bits,
+                FixedGenerators::NoteCommitmentRandomization,
+                params
+            ).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(q.x.get_value().unwrap(), x1);
+            assert_eq!(q.y.get_value().unwrap(), y1);
+        }
+    }
+
+    #[test]
+    fn test_edwards_fixed_base_mul_cost() {
+        let params = &JubjubBls12::new();
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        let p = params.generator(FixedGenerators::NoteCommitmentRandomization);
+        let (x, y) = p.into_xy();
+
+        let s_bits: Vec<bool> = (0..252).map(|_| rng.gen()).collect();
+
+        // The windowed fixed-base multiplication.
+        let windowed = {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let bits = s_bits.iter().enumerate().map(|(i, b)| {
+                Boolean::from(AllocatedBit::alloc(cs.namespace(|| format!("scalar bit {}", i)), Some(*b)).unwrap())
+            }).collect::<Vec<_>>();
+
+            let before = cs.num_constraints();
+            let q = EdwardsPoint::fixed_base_mul(
+                cs.namespace(|| "multiplication"),
+                &bits,
+                FixedGenerators::NoteCommitmentRandomization,
+                params
+            ).unwrap();
+
+            assert!(cs.is_satisfied());
+
+            (cs.num_constraints() - before, q.x.get_value().unwrap(), q.y.get_value().unwrap())
+        };
+
+        // The naive double-and-add over the same base.
+        let naive = {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let bits = s_bits.iter().enumerate().map(|(i, b)| {
+                Boolean::from(AllocatedBit::alloc(cs.namespace(|| format!("scalar bit {}", i)), Some(*b)).unwrap())
+            }).collect::<Vec<_>>();
+
+            let base = EdwardsPoint {
+                x: AllocatedNum::alloc(cs.namespace(|| "x"), || Ok(x)).unwrap(),
+                y: AllocatedNum::alloc(cs.namespace(|| "y"), || Ok(y)).unwrap()
+            };
+
+            let before = cs.num_constraints();
+            let q = base.mul(cs.namespace(|| "multiplication"), &bits, params).unwrap();
+
+            assert!(cs.is_satisfied());
+
+            (cs.num_constraints() - before, q.x.get_value().unwrap(), q.y.get_value().unwrap())
+        };
+
+        assert_eq!((windowed.1, windowed.2), (naive.1, naive.2));
+
+        // 84 lookups of 3 constraints and 83 additions of 6,
+        // against a doubling and a selected addition per bit.
+        assert_eq!(windowed.0, 84 * 3 + 83 * 6);
+        assert_eq!(naive.0, 3265);
+        assert!(windowed.0 * 4 < naive.0);
+    }
+
+    #[test]
+    fn test_select() {
+        let params = &JubjubBls12::new();
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..100 {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let (x0, y0) = edwards::Point::<Bls12, _>::rand(rng, params).into_xy();
+            let (x1, y1) = edwards::Point::<Bls12, _>::rand(rng, params).into_xy();
+
+            let a = EdwardsPoint {
+                x: AllocatedNum::alloc(cs.namespace(|| "a x"), || Ok(x0)).unwrap(),
+                y: AllocatedNum::alloc(cs.namespace(|| "a y"), || Ok(y0)).unwrap()
+            };
+            let b = EdwardsPoint {
+                x: AllocatedNum::alloc(cs.namespace(|| "b x"), || Ok(x1)).unwrap(),
+                y: AllocatedNum::alloc(cs.namespace(|| "b y"), || Ok(y1)).unwrap()
+            };
+
+            let condition_value = rng.gen();
+            let condition = Boolean::from(
+                AllocatedBit::alloc(cs.namespace(|| "condition"), Some(condition_value)).unwrap()
+            );
+
+            let c = EdwardsPoint::select(cs.namespace(|| "select"), &a, &b, &condition).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(cs.num_constraints(), 3);
+
+            if condition_value {
+                assert_eq!(c.x.get_value().unwrap(), x0);
+                assert_eq!(c.y.get_value().unwrap(), y0);
+            } else {
+                assert_eq!(c.x.get_value().unwrap(), x1);
+                assert_eq!(c.y.get_value().unwrap(), y1);
+            }
+
+            let c = EdwardsPoint::select(cs.namespace(|| "select constant true"), &a, &b, &Boolean::constant(true)).unwrap();
+            assert_eq!(c.x.get_value().unwrap(), x0);
+            assert_eq!(c.y.get_value().unwrap(), y0);
+
+            let c = EdwardsPoint::select(cs.namespace(|| "select constant false"), &a, &b, &Boolean::constant(false)).unwrap();
+            assert_eq!(c.x.get_value().unwrap(), x1);
+            assert_eq!(c.y.get_value().unwrap(), y1);
+
+            assert_eq!(cs.num_constraints(), 3);
+        }
+    }
+
+    #[test]
+    fn test_edwards_conditionally_reverse() {
+        let params = &JubjubBls12::new();
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..100 {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let (x0, y0) = edwards::Point::<Bls12, _>::rand(rng, params).into_xy();
+            let (x1, y1) = edwards::Point::<Bls12, _>::rand(rng, params).into_xy();
+
+            let a = EdwardsPoint {
+                x: AllocatedNum::alloc(cs.namespace(|| "a x"), || Ok(x0)).unwrap(),
+                y: AllocatedNum::alloc(cs.namespace(|| "a y"), || Ok(y0)).unwrap()
+            };
+            let b = EdwardsPoint {
+                x: AllocatedNum::alloc(cs.namespace(|| "b x"), || Ok(x1)).unwrap(),
+                y: AllocatedNum::alloc(cs.namespace(|| "b y"), || Ok(y1)).unwrap()
+            };
+
+            let condition_value = rng.gen();
+            let condition = Boolean::from(
+                AllocatedBit::alloc(cs.namespace(|| "condition"), Some(condition_value)).unwrap()
+            );
+
+            let (c, d) = EdwardsPoint::conditionally_reverse(cs.namespace(|| "reverse"), &a, &b, &condition).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(cs.num_constraints(), 5);
+
+            // Each coordinate agrees with reversing the
+            // numbers themselves.
+            let (x, _) = AllocatedNum::conditionally_reverse(cs.namespace(|| "reverse x"), &a.x, &b.x, &condition).unwrap();
+            let (_, y) = AllocatedNum::conditionally_reverse(cs.namespace(|| "reverse y"), &a.y, &b.y, &condition).unwrap();
+            assert_eq!(c.x.get_value(), x.get_value());
+            assert_eq!(d.y.get_value(), y.get_value());
+
+            if condition_value {
+                assert_eq!((c.x.get_value().unwrap(), c.y.get_value().unwrap()), (x1, y1));
+                assert_eq!((d.x.get_value().unwrap(), d.y.get_value().unwrap()), (x0, y0));
+            } else {
+                assert_eq!((c.x.get_value().unwrap(), c.y.get_value().unwrap()), (x0, y0));
+                assert_eq!((d.x.get_value().unwrap(), d.y.get_value().unwrap()), (x1, y1));
+            }
+
+            cs.set("reverse/y/conditional reversal result 2/num", rng.gen());
+            assert_eq!(cs.which_is_unsatisfied(), Some("reverse/y/second conditional reversal"));
+        }
+    }
+
+    #[test]
+    fn test_compressed_edwards() {
+        let params = &JubjubBls12::new();
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..10 {
+            let p = edwards::Point::<Bls12, _>::rand(rng, params);
+            let compressed = CompressedEdwards::from_native(&p);
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+            let q = compressed.witness_into_circuit(cs.namespace(|| "witness"), params).unwrap();
+
+            assert!(cs.is_satisfied());
+
+            let q = edwards::Point::<Bls12, Unknown>::get_for_y(
+                q.y.get_value().unwrap(),
+                q.x.get_value().unwrap().into_repr().is_odd(),
+                params
+            ).unwrap();
+            assert!(q == p);
+
+            // The bits agree with the circuit encoding.
+            let bits = EdwardsPoint::<Bls12, _> {
+                x: AllocatedNum::alloc(cs.namespace(|| "x"), || Ok(p.into_xy().0)).unwrap(),
+                y: AllocatedNum::alloc(cs.namespace(|| "y"), || Ok(p.into_xy().1)).unwrap()
+            }.into_bits(cs.namespace(|| "into bits")).unwrap();
+            assert_eq!(
+                bits.iter().map(|b| b.get_value().unwrap()).collect::<Vec<_>>(),
+                compressed.get_bits()
+            );
+        }
+    }
+
+    #[test]
+    fn test_edwards_multi_mul() {
+        let params = &JubjubBls12::new();
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..5 {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let points: Vec<_> = (0..3).map(|_| edwards::Point::<Bls12, _>::rand(rng, params)).collect();
+            let scalars: Vec<Fs> = (0..3).map(|_| Fs::rand(rng)).collect();
+
+            // The last scalar is much shorter than the others.
+            let lengths = [Fs::NUM_BITS as usize, Fs::NUM_BITS as usize, 10];
+
+            let mut expected = edwards::Point::zero();
+            let mut bases = vec![];
+            let mut scalar_bits = vec![];
+            for (i, ((p, s), &len)) in points.iter().zip(scalars.iter()).zip(lengths.iter()).enumerate() {
+                let mut bits = BitIterator::new(s.into_repr()).collect::<Vec<_>>();
+                bits.reverse();
+                bits.truncate(len);
+
+                let mut truncated = Fs::zero();
+                for &b in bits.iter().rev() {
+                    truncated.double();
+                    if b {
+                        truncated.add_assign(&Fs::one());
+                    }
+                }
+                expected = expected.add(&p.mul(truncated, params), params);
+
+                let (x, y) = p.into_xy();
+                bases.push(EdwardsPoint {
+                    x: AllocatedNum::alloc(cs.namespace(|| format!("x {}", i)), || Ok(x)).unwrap(),
+                    y: AllocatedNum::alloc(cs.namespace(|| format!("y {}", i)), || Ok(y)).unwrap()
+                });
+                scalar_bits.push(bits.into_iter().enumerate().map(|(j, b)| {
+                    Boolean::from(
+                        AllocatedBit::alloc(cs.namespace(|| format!("scalar {} bit {}", i, j)), Some(b)).unwrap()
+                    )
+                }).collect::<Vec<_>>());
+            }
+
+            let num_inputs = cs.num_constraints();
+
+            let q = EdwardsPoint::multi_mul(
+                cs.namespace(|| "multi mul"),
+                &scalar_bits,
+                &bases,
+                params
+            ).unwrap();
+
+            assert!(cs.is_satisfied());
+
+            let (x, y) = expected.into_xy();
+            assert_eq!(q.x.get_value().unwrap(), x);
+            assert_eq!(q.y.get_value().unwrap(), y);
+
+            // 251 doublings are shared, rather than being
+            // done for each scalar.
+            let selections = 2 * 252 + 10;
+            assert_eq!(
+                cs.num_constraints() - num_inputs,
+                251 * 5 + selections * 2 + (selections - 1) * 6
+            );
+        }
+    }
+
+    #[test]
+    fn test_edwards_multiplication() {
+        let params = &JubjubBls12::new();
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..100 {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let p = edwards::Point::<Bls12, _>::rand(rng, params);
+            let s = Fs::rand(rng);
+            let q = p.mul(s, params);
+
+            let (x0, y0) = p.into_xy();
+            let (x1, y1) = q.into_xy();
+
+            let num_x0 = AllocatedNum::alloc(cs.namespace(|| "x0"), || {
+                Ok(x0)
+            }).unwrap();
+            let num_y0 = AllocatedNum::alloc(cs.namespace(|| "y0"), || {
+                Ok(y0)
+            }).unwrap();
+
+            let p = EdwardsPoint {
+                x: num_x0,
+                y: num_y0
+            };
+
+            let mut s_bits = BitIterator::new(s.into_repr()).collect::<Vec<_>>();
+            s_bits.reverse();
+            s_bits.truncate(Fs::NUM_BITS as usize);
+
+            let s_bits = s_bits.into_iter()
+                               .enumerate()
+                               .map(|(i, b)| AllocatedBit::alloc(cs.namespace(|| format!("scalar bit {}", i)), Some(b)).unwrap())
+                               .map(|v| Boolean::from(v))
+                               .collect::<Vec<_>>();
+
+            let q = p.mul(
+                cs.namespace(|| "scalar mul"),
+                &s_bits,
+                params
+            ).unwrap();
+
+            assert!(cs.is_satisfied());
+
+            assert_eq!(
+                q.x.get_value().unwrap(),
+                x1
+            );
+
+            assert_eq!(
+                q.y.get_value().unwrap(),
+                y1
+            );
+
+            let s_bits: Vec<_> = s_bits.into_iter().rev().collect();
+
+            let q = p.mul_msb_first(
+                cs.namespace(|| "scalar mul msb first"),
+                &s_bits,
+                params
+            ).unwrap();
+
+            assert!(cs.is_satisfied());
+
+            assert_eq!(q.x.get_value().unwrap(), x1);
+            assert_eq!(q.y.get_value().unwrap(), y1);
+        }
+    }
+
+    #[test]
+    fn test_conditionally_select() {
+        let params = &JubjubBls12::new();
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..1000 {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let p = edwards::Point::<Bls12, _>::rand(rng, params);
+
+            let (x0, y0) = p.into_xy();
+
+            let num_x0 = AllocatedNum::alloc(cs.namespace(|| "x0"), || {
+                Ok(x0)
+            }).unwrap();
+            let num_y0 = AllocatedNum::alloc(cs.namespace(|| "y0"), || {
+                Ok(y0)
+            }).unwrap();
+
+            let p = EdwardsPoint {
+                x: num_x0,
+                y: num_y0
+            };
+
+            let mut should_we_select = rng.gen();
+
+            // Conditionally allocate
+            let mut b = if rng.gen() {
+                Boolean::from(AllocatedBit::alloc(
+                    cs.namespace(|| "condition"),
+                    Some(should_we_select)
+                ).unwrap())
+            } else {
+                Boolean::constant(should_we_select)
+            };
+
+            // Conditionally negate
+            if rng.gen() {
+                b = b.not();
+                should_we_select = !should_we_select;
+            }
+
+            let q = p.conditionally_select(cs.namespace(|| "select"), &b).unwrap();
+
+            assert!(cs.is_satisfied());
+
+            if should_we_select {
+                assert_eq!(q.x.get_value().unwrap(), x0);
+                assert_eq!(q.y.get_value().unwrap(), y0);
+
+                cs.set("select/y'/num", Fr::one());
+                assert_eq!(cs.which_is_unsatisfied().unwrap(), "select/y' computation");
+                cs.set("select/x'/num", Fr::zero());
+                assert_eq!(cs.which_is_unsatisfied().unwrap(), "select/x' computation");
+            } else {
+                assert_eq!(q.x.get_value().unwrap(), Fr::zero());
+                assert_eq!(q.y.get_value().unwrap(), Fr::one());
+
+                cs.set("select/y'/num", x0);
+                assert_eq!(cs.which_is_unsatisfied().unwrap(), "select/y' computation");
+                cs.set("select/x'/num", y0);
+                assert_eq!(cs.which_is_unsatisfied().unwrap(), "select/x' computation");
+            }
+        }
+    }
+
+    #[test]
+    fn test_edwards_add_constant() {
+        let params = &JubjubBls12::new();
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..20 {
+            let p = edwards::Point::<Bls12, _>::rand(rng, params);
+            let (x, y) = p.into_xy();
+
+            let constants = vec![
+                edwards::Point::<Bls12, PrimeOrder>::zero(),
+                edwards::Point::<Bls12, _>::rand(rng, params).mul_by_cofactor(params)
+            ];
+
+            for constant in constants {
+                let expected = p.add(&constant.clone().into(), params).into_xy();
+
+                let mut cs = TestConstraintSystem::<Bls12>::new();
+
+                let q = EdwardsPoint {
+                    x: AllocatedNum::alloc(cs.namespace(|| "x"), || Ok(x)).unwrap(),
+                    y: AllocatedNum::alloc(cs.namespace(|| "y"), || Ok(y)).unwrap()
+                };
+
+                let r = q.add_constant(cs.namespace(|| "addition"), &constant, params).unwrap();
+
+                assert!(cs.is_satisfied());
+                assert_eq!(cs.num_constraints(), 3);
+                assert_eq!((r.x.get_value().unwrap(), r.y.get_value().unwrap()), expected);
+
+                if constant == edwards::Point::zero() {
+                    assert_eq!(expected, (x, y));
+                }
+
+                cs.set("addition/y3/num", rng.gen());
+                assert_eq!(cs.which_is_unsatisfied(), Some("addition/y3 computation"));
+                cs.set("addition/x3/num", rng.gen());
+                assert_eq!(cs.which_is_unsatisfied(), Some("addition/x3 computation"));
+                cs.set("addition/C/num", rng.gen());
+                assert_eq!(cs.which_is_unsatisfied(), Some("addition/C computation"));
+            }
+        }
+    }
+
+    #[test]
+    fn test_edwards_addition() {
+        let params = &JubjubBls12::new();
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..100 {
+            let p1 = edwards::Point::<Bls12, _>::rand(rng, params);
+            let p2 = edwards::Point::<Bls12, _>::rand(rng, params);
+
+            let p3 = p1.add(&p2, params);
+
+            let (x0, y0) = p1.into_xy();
+            let (x1, y1) = p2.into_xy();
+            let (x2, y2) = p3.into_xy();
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let num_x0 = AllocatedNum::alloc(cs.namespace(|| "x0"), || {
+                Ok(x0)
+            }).unwrap();
+            let num_y0 = AllocatedNum::alloc(cs.namespace(|| "y0"), || {
+                Ok(y0)
+            }).unwrap();
+
+            let num_x1 = AllocatedNum::alloc(cs.namespace(|| "x1"), || {
+                Ok(x1)
+            }).unwrap();
+            let num_y1 = AllocatedNum::alloc(cs.namespace(|| "y1"), || {
+                Ok(y1)
+            }).unwrap();
+
+            let p1 = EdwardsPoint {
+                x: num_x0,
+                y: num_y0
+            };
+
+            let p2 = EdwardsPoint {
+                x: num_x1,
+                y: num_y1
+            };
+
+            let p3 = p1.add(cs.namespace(|| "addition"), &p2, params).unwrap();
+
+            assert!(cs.is_satisfied());
+
+            assert!(p3.x.get_value().unwrap() == x2);
+            assert!(p3.y.get_value().unwrap() == y2);
+
+            let u = cs.get("addition/U/num");
+            cs.set("addition/U/num", rng.gen());
+            assert_eq!(cs.which_is_unsatisfied(), Some("addition/U computation"));
+            cs.set("addition/U/num", u);
+            assert!(cs.is_satisfied());
+
+            let x3 = cs.get("addition/x3/num");
+            cs.set("addition/x3/num", rng.gen());
+            assert_eq!(cs.which_is_unsatisfied(), Some("addition/x3 computation"));
+            cs.set("addition/x3/num", x3);
+            assert!(cs.is_satisfied());
+
+            let y3 = cs.get("addition/y3/num");
+            cs.set("addition/y3/num", rng.gen());
+            assert_eq!(cs.which_is_unsatisfied(), Some("addition/y3 computation"));
+            cs.set("addition/y3/num", y3);
+            assert!(cs.is_satisfied());
+        }
+    }
+
+    #[test]
+    fn test_edwards_doubling() {
+        let params = &JubjubBls12::new();
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..100 {
+            let p1 = edwards::Point::<Bls12, _>::rand(rng, params);
+            let p2 = p1.double(params);
+
+            let (x0, y0) = p1.into_xy();
+            let (x1, y1) = p2.into_xy();
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let num_x0 = AllocatedNum::alloc(cs.namespace(|| "x0"), || {
+                Ok(x0)
+            }).unwrap();
+            let num_y0 = AllocatedNum::alloc(cs.namespace(|| "y0"), || {
+                Ok(y0)
+            }).unwrap();
+
+            let p1 = EdwardsPoint {
+                x: num_x0,
+                y: num_y0
+            };
+
+            let p2 = p1.double(cs.namespace(|| "doubling"), params).unwrap();
+
+            assert!(cs.is_satisfied());
+
+            assert!(p2.x.get_value().unwrap() == x1);
+            assert!(p2.y.get_value().unwrap() == y1);
+
+            let p3 = p1.add(cs.namespace(|| "addition"), &p1, params).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(cs.num_constraints(), 5 + 6);
+
+            assert!(p3.x.get_value().unwrap() == x1);
+            assert!(p3.y.get_value().unwrap() == y1);
+
+            let t = cs.get("doubling/T/num");
+            cs.set("doubling/T/num", rng.gen());
+            assert_eq!(cs.which_is_unsatisfied(), Some("doubling/T computation"));
+            cs.set("doubling/T/num", t);
+            assert!(cs.is_satisfied());
+
+            let x3 = cs.get("doubling/x3/num");
+            cs.set("doubling/x3/num", rng.gen());
+            assert_eq!(cs.which_is_unsatisfied(), Some("doubling/x3 computation"));
+            cs.set("doubling/x3/num", x3);
+            assert!(cs.is_satisfied());
+
+            let y3 = cs.get("doubling/y3/num");
+            cs.set("doubling/y3/num", rng.gen());
+            assert_eq!(cs.which_is_unsatisfied(), Some("doubling/y3 computation"));
+            cs.set("doubling/y3/num", y3);
+            assert!(cs.is_satisfied());
+        }
+    }
+}
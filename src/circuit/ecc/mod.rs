@@ -0,0 +1,8 @@
+//! Gadgets for the twisted Edwards form of the Jubjub
+//! curve and its birationally equivalent Montgomery form.
+
+mod edwards;
+mod montgomery;
+
+pub use self::edwards::*;
+pub use self::montgomery::*;
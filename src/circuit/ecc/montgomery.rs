@@ -0,0 +1,779 @@
+use pairing::{
+    Engine,
+    Field
+};
+
+use bellman::{
+    SynthesisError,
+    ConstraintSystem,
+    LinearCombination
+};
+
+use super::super::{
+    Assignment
+};
+
+use super::super::num::AllocatedNum;
+
+use ::jubjub::{
+    JubjubEngine,
+    JubjubParams
+};
+
+use super::edwards::EdwardsPoint;
+
+pub struct MontgomeryPoint<E: Engine, Var> {
+    x: AllocatedNum<E, Var>,
+    y: AllocatedNum<E, Var>
+}
+
+impl<E: JubjubEngine, Var: Copy> MontgomeryPoint<E, Var> {
+    /// Converts a point on the Montgomery curve into
+    /// a point in the birationally equivalent twisted
+    /// Edwards curve.
+    ///
+    /// The map is undefined where y = 0 or x = -1. On
+    /// the curve, y = 0 only at the point of order two
+    /// (0, 0), which is mapped to (0, -1) as in
+    /// `edwards::Point::from_montgomery`; x = -1 is not
+    /// on the curve at all. The Montgomery identity has
+    /// no affine representation, so it cannot be given.
+    pub fn into_edwards<CS>(
+        &self,
+        mut cs: CS,
+        params: &E::Params
+    ) -> Result<EdwardsPoint<E, Var>, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        // Compute k = scale / y, or zero if y = 0
+        let k = AllocatedNum::alloc(cs.namespace(|| "k"), || {
+            match self.y.get_value().get()?.inverse() {
+                Some(mut invy) => {
+                    invy.mul_assign(params.scale());
+
+                    Ok(invy)
+                },
+                None => {
+                    Ok(E::Fr::zero())
+                }
+            }
+        })?;
+
+        // Compute u = (scale*x) / y
+        let u = AllocatedNum::alloc(cs.namespace(|| "u"), || {
+            let mut t0 = *self.x.get_value().get()?;
+            t0.mul_assign(k.get_value().get()?);
+
+            Ok(t0)
+        })?;
+
+        cs.enforce(
+            || "u computation",
+            LinearCombination::<Var, E>::zero() + self.y.get_variable(),
+            LinearCombination::<Var, E>::zero() + u.get_variable(),
+            LinearCombination::<Var, E>::zero() + (*params.scale(), self.x.get_variable())
+        );
+
+        // When y = 0 the constraint above holds for any u,
+        // so we also enforce u = x * k, which forces u = 0
+        // at (0, 0) and is otherwise implied by k = scale / y.
+        cs.enforce(
+            || "u degeneracy",
+            LinearCombination::<Var, E>::zero() + self.x.get_variable(),
+            LinearCombination::<Var, E>::zero() + k.get_variable(),
+            LinearCombination::<Var, E>::zero() + u.get_variable()
+        );
+
+        // Compute v = (x - 1) / (x + 1)
+        let v = AllocatedNum::alloc(cs.namespace(|| "v"), || {
+            let mut t0 = *self.x.get_value().get()?;
+            let mut t1 = t0;
+            t0.sub_assign(&E::Fr::one());
+            t1.add_assign(&E::Fr::one());
+
+            match t1.inverse() {
+                Some(t1) => {
+                    t0.mul_assign(&t1);
+
+                    Ok(t0)
+                },
+                None => {
+                    Err(SynthesisError::AssignmentMissing)
+                }
+            }
+        })?;
+
+        let one = cs.one();
+        cs.enforce(
+            || "v computation",
+            LinearCombination::<Var, E>::zero() + self.x.get_variable()
+                                      + one,
+            LinearCombination::<Var, E>::zero() + v.get_variable(),
+            LinearCombination::<Var, E>::zero() + self.x.get_variable()
+                                      - one,
+        );
+
+        Ok(EdwardsPoint {
+            x: u,
+            y: v
+        })
+    }
+
+    /// Interprets an (x, y) pair as a point in
+    /// Montgomery, enforcing that it's on the
+    /// curve, at the cost of three constraints.
+    pub fn interpret<CS>(
+        mut cs: CS,
+        x: &AllocatedNum<E, Var>,
+        y: &AllocatedNum<E, Var>,
+        params: &E::Params
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        // y^2 = x^3 + A*x^2 + x = (x^2 + A*x + 1) * x
+
+        let x2 = x.square(cs.namespace(|| "x^2"))?;
+        let y2 = y.square(cs.namespace(|| "y^2"))?;
+
+        let one = cs.one();
+        cs.enforce(
+            || "on curve check",
+            LinearCombination::zero() + x2.get_variable()
+                                      + (*params.montgomery_a(), x.get_variable())
+                                      + one,
+            LinearCombination::zero() + x.get_variable(),
+            LinearCombination::zero() + y2.get_variable()
+        );
+
+        Ok(MontgomeryPoint {
+            x: x.clone(),
+            y: y.clone()
+        })
+    }
+
+    /// Interprets an (x, y) pair as a point
+    /// in Montgomery, does not check that it's
+    /// on the curve. Useful for constants and
+    /// window table lookups.
+    pub fn interpret_unchecked(
+        x: AllocatedNum<E, Var>,
+        y: AllocatedNum<E, Var>
+    ) -> Self
+    {
+        MontgomeryPoint {
+            x: x,
+            y: y
+        }
+    }
+
+    /// Performs an affine point addition, not defined for
+    /// coincident points.
+    ///
+    /// The inputs must not share an x-coordinate. For a
+    /// point and its negation the constraints are
+    /// unsatisfiable, and for a point and itself "evaluate
+    /// lambda" holds for any lambda, which is unsound.
+    /// Use `checked_add` unless the inputs are known to
+    /// be distinct, as in the Pedersen hash windows.
+    pub fn add<CS>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+        params: &E::Params
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        // Compute lambda = (y' - y) / (x' - x)
+        let lambda = AllocatedNum::alloc(cs.namespace(|| "lambda"), || {
+            let mut n = *other.y.get_value().get()?;
+            n.sub_assign(self.y.get_value().get()?);
+
+            let mut d = *other.x.get_value().get()?;
+            d.sub_assign(self.x.get_value().get()?);
+
+            match d.inverse() {
+                Some(d) => {
+                    n.mul_assign(&d);
+                    Ok(n)
+                },
+                None => {
+                    Err(SynthesisError::AssignmentMissing)
+                }
+            }
+        })?;
+
+        cs.enforce(
+            || "evaluate lambda",
+            LinearCombination::<Var, E>::zero() + other.x.get_variable()
+                                                - self.x.get_variable(),
+
+            LinearCombination::zero()           + lambda.get_variable(),
+
+            LinearCombination::<Var, E>::zero() + other.y.get_variable()
+                                                - self.y.get_variable()
+        );
+
+        // Compute x'' = lambda^2 - A - x - x'
+        let xprime = AllocatedNum::alloc(cs.namespace(|| "xprime"), || {
+            let mut t0 = *lambda.get_value().get()?;
+            t0.square();
+            t0.sub_assign(params.montgomery_a());
+            t0.sub_assign(self.x.get_value().get()?);
+            t0.sub_assign(other.x.get_value().get()?);
+
+            Ok(t0)
+        })?;
+
+        // (lambda) * (lambda) = (A + x + x' + x'')
+        let one = cs.one();
+        cs.enforce(
+            || "evaluate xprime",
+            LinearCombination::zero()           + lambda.get_variable(),
+            LinearCombination::zero()           + lambda.get_variable(),
+            LinearCombination::<Var, E>::zero() + (*params.montgomery_a(), one)
+                                                + self.x.get_variable()
+                                                + other.x.get_variable()
+                                                + xprime.get_variable()
+        );
+
+        // Compute y' = -(y + lambda(x' - x))
+        let yprime = AllocatedNum::alloc(cs.namespace(|| "yprime"), || {
+            let mut t0 = *xprime.get_value().get()?;
+            t0.sub_assign(self.x.get_value().get()?);
+            t0.mul_assign(lambda.get_value().get()?);
+            t0.add_assign(self.y.get_value().get()?);
+            t0.negate();
+
+            Ok(t0)
+        })?;
+
+        // y' + y = lambda(x - x')
+        cs.enforce(
+            || "evaluate yprime",
+            LinearCombination::zero()           + self.x.get_variable()
+                                                - xprime.get_variable(),
+
+            LinearCombination::zero()           + lambda.get_variable(),
+
+            LinearCombination::<Var, E>::zero() + yprime.get_variable()
+                                                + self.y.get_variable()
+        );
+
+        Ok(MontgomeryPoint {
+            x: xprime,
+            y: yprime
+        })
+    }
+
+    /// Same as `add`, but first enforces that the inputs
+    /// have distinct x-coordinates, at the cost of one
+    /// more constraint.
+    pub fn checked_add<CS>(
+        &self,
+        mut cs: CS,
+        other: &Self,
+        params: &E::Params
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        self.x.enforce_not_equal(cs.namespace(|| "x inequality"), &other.x)?;
+
+        self.add(cs.namespace(|| "addition"), other, params)
+    }
+
+    /// Performs an affine point doubling, not defined for
+    /// the point of order two (0, 0).
+    pub fn double<CS>(
+        &self,
+        mut cs: CS,
+        params: &E::Params
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        // Square x
+        let xx = self.x.square(&mut cs)?;
+
+        // Compute lambda = (3.xx + 2.A.x + 1) / 2.y
+        let lambda = AllocatedNum::alloc(cs.namespace(|| "lambda"), || {
+            let mut t0 = *xx.get_value().get()?;
+            let mut t1 = t0;
+            t0.double(); // t0 = 2.xx
+            t0.add_assign(&t1); // t0 = 3.xx
+            t1 = *self.x.get_value().get()?; // t1 = x
+            t1.mul_assign(params.montgomery_2a()); // t1 = 2.A.x
+            t0.add_assign(&t1);
+            t0.add_assign(&E::Fr::one());
+            t1 = *self.y.get_value().get()?; // t1 = y
+            t1.double(); // t1 = 2.y
+            match t1.inverse() {
+                Some(t1) => {
+                    t0.mul_assign(&t1);
+
+                    Ok(t0)
+                },
+                None => {
+                    Err(SynthesisError::AssignmentMissing)
+                }
+            }
+        })?;
+
+        // (2.y) * (lambda) = (3.xx + 2.A.x + 1)
+        let one = cs.one();
+        cs.enforce(
+            || "evaluate lambda",
+            LinearCombination::<Var, E>::zero() + self.y.get_variable()
+                                                + self.y.get_variable(),
+
+            LinearCombination::zero()           + lambda.get_variable(),
+
+            LinearCombination::<Var, E>::zero() + xx.get_variable()
+                                                + xx.get_variable()
+                                                + xx.get_variable()
+                                                + (*params.montgomery_2a(), self.x.get_variable())
+                                                + one
+        );
+
+        // Compute x' = (lambda^2) - A - 2.x
+        let xprime = AllocatedNum::alloc(cs.namespace(|| "xprime"), || {
+            let mut t0 = *lambda.get_value().get()?;
+            t0.square();
+            t0.sub_assign(params.montgomery_a());
+            t0.sub_assign(self.x.get_value().get()?);
+            t0.sub_assign(self.x.get_value().get()?);
+
+            Ok(t0)
+        })?;
+
+        // (lambda) * (lambda) = (A + 2.x + x')
+        cs.enforce(
+            || "evaluate xprime",
+            LinearCombination::zero()           + lambda.get_variable(),
+            LinearCombination::zero()           + lambda.get_variable(),
+            LinearCombination::<Var, E>::zero() + (*params.montgomery_a(), one)
+                                                + self.x.get_variable()
+                                                + self.x.get_variable()
+                                                + xprime.get_variable()
+        );
+
+        // Compute y' = -(y + lambda(x' - x))
+        let yprime = AllocatedNum::alloc(cs.namespace(|| "yprime"), || {
+            let mut t0 = *xprime.get_value().get()?;
+            t0.sub_assign(self.x.get_value().get()?);
+            t0.mul_assign(lambda.get_value().get()?);
+            t0.add_assign(self.y.get_value().get()?);
+            t0.negate();
+
+            Ok(t0)
+        })?;
+
+        // y' + y = lambda(x - x')
+        cs.enforce(
+            || "evaluate yprime",
+            LinearCombination::zero()           + self.x.get_variable()
+                                                - xprime.get_variable(),
+
+            LinearCombination::zero()           + lambda.get_variable(),
+
+            LinearCombination::<Var, E>::zero() + yprime.get_variable()
+                                                + self.y.get_variable()
+        );
+
+        Ok(MontgomeryPoint {
+            x: xprime,
+            y: yprime
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bellman::{ConstraintSystem};
+    use rand::{XorShiftRng, SeedableRng, Rng};
+    use pairing::bls12_381::{Bls12, Fr};
+    use pairing::{Field, PrimeField};
+    use ::circuit::test::*;
+    use ::jubjub::{
+        montgomery,
+        edwards,
+        JubjubBls12,
+        JubjubParams
+    };
+    use super::MontgomeryPoint;
+    use super::super::EdwardsPoint;
+    use ::circuit::num::AllocatedNum;
+
+    #[test]
+    fn test_into_edwards_constants() {
+        let params = &JubjubBls12::new();
+        let rng = &mut XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        // -(A + 2) = s^2
+        let mut s2 = *params.scale();
+        s2.square();
+        s2.add_assign(params.montgomery_a());
+        s2.add_assign(&Fr::from_str("2").unwrap());
+        assert!(s2.is_zero());
+
+        let p = montgomery::Point::<Bls12, _>::rand(rng, params);
+        let (x, y) = p.into_xy().unwrap();
+
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+        let numx = AllocatedNum::alloc(cs.namespace(|| "mont x"), || Ok(x)).unwrap();
+        let numy = AllocatedNum::alloc(cs.namespace(|| "mont y"), || Ok(y)).unwrap();
+        let q = MontgomeryPoint::interpret_unchecked(numx, numy).into_edwards(&mut cs, params).unwrap();
+        assert!(cs.is_satisfied());
+
+        // u = s x / y
+        let mut u = x;
+        u.mul_assign(params.scale());
+        u.mul_assign(&y.inverse().unwrap());
+
+        // v = (x - 1) / (x + 1)
+        let mut v = x;
+        v.sub_assign(&Fr::one());
+        let mut tmp = x;
+        tmp.add_assign(&Fr::one());
+        v.mul_assign(&tmp.inverse().unwrap());
+
+        assert_eq!(q.x.get_value().unwrap(), u);
+        assert_eq!(q.y.get_value().unwrap(), v);
+    }
+
+    #[test]
+    fn test_into_edwards() {
+        let params = &JubjubBls12::new();
+        let rng = &mut XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..100 {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let p = montgomery::Point::<Bls12, _>::rand(rng, params);
+            let (u, v) = edwards::Point::from_montgomery(&p, params).into_xy();
+            let (x, y) = p.into_xy().unwrap();
+
+            let numx = AllocatedNum::alloc(cs.namespace(|| "mont x"), || {
+                Ok(x)
+            }).unwrap();
+            let numy = AllocatedNum::alloc(cs.namespace(|| "mont y"), || {
+                Ok(y)
+            }).unwrap();
+
+            let p = MontgomeryPoint::interpret_unchecked(numx, numy);
+
+            let q = p.into_edwards(&mut cs, params).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert!(q.x.get_value().unwrap() == u);
+            assert!(q.y.get_value().unwrap() == v);
+
+            cs.set("u/num", rng.gen());
+            assert_eq!(cs.which_is_unsatisfied().unwrap(), "u computation");
+            cs.set("u/num", u);
+            assert!(cs.is_satisfied());
+
+            cs.set("v/num", rng.gen());
+            assert_eq!(cs.which_is_unsatisfied().unwrap(), "v computation");
+            cs.set("v/num", v);
+            assert!(cs.is_satisfied());
+        }
+    }
+
+    #[test]
+    fn test_into_edwards_order_two() {
+        let params = &JubjubBls12::new();
+        let rng = &mut XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        // (0, 0) is the only Montgomery point with y = 0.
+        let numx = AllocatedNum::alloc(cs.namespace(|| "mont x"), || {
+            Ok(Fr::zero())
+        }).unwrap();
+        let numy = AllocatedNum::alloc(cs.namespace(|| "mont y"), || {
+            Ok(Fr::zero())
+        }).unwrap();
+
+        let p = MontgomeryPoint::interpret_unchecked(numx, numy);
+
+        let q = p.into_edwards(cs.namespace(|| "conversion"), params).unwrap();
+
+        assert!(cs.is_satisfied());
+
+        let mut neg1 = Fr::one();
+        neg1.negate();
+
+        assert!(q.x.get_value().unwrap() == Fr::zero());
+        assert!(q.y.get_value().unwrap() == neg1);
+
+        EdwardsPoint::interpret(cs.namespace(|| "on curve"), &q.x, &q.y, params).unwrap();
+        assert!(cs.is_satisfied());
+
+        // u is not free, even though y * u = scale * x is.
+        cs.set("conversion/u/num", rng.gen());
+        assert_eq!(cs.which_is_unsatisfied().unwrap(), "conversion/u degeneracy");
+    }
+
+    #[test]
+    fn test_montgomery_interpret() {
+        let params = &JubjubBls12::new();
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..100 {
+            let p = montgomery::Point::<Bls12, _>::rand(rng, params);
+            let (x, y) = p.into_xy().unwrap();
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+            let numx = AllocatedNum::alloc(cs.namespace(|| "x"), || {
+                Ok(x)
+            }).unwrap();
+            let numy = AllocatedNum::alloc(cs.namespace(|| "y"), || {
+                Ok(y)
+            }).unwrap();
+
+            let p = MontgomeryPoint::interpret(&mut cs, &numx, &numy, params).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(cs.num_constraints(), 3);
+            assert_eq!(p.x.get_value().unwrap(), x);
+            assert_eq!(p.y.get_value().unwrap(), y);
+
+            // Perturbing y takes it off the curve.
+            let mut y = y;
+            y.add_assign(&Fr::one());
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+            let numx = AllocatedNum::alloc(cs.namespace(|| "x"), || {
+                Ok(x)
+            }).unwrap();
+            let numy = AllocatedNum::alloc(cs.namespace(|| "y"), || {
+                Ok(y)
+            }).unwrap();
+
+            MontgomeryPoint::interpret(&mut cs, &numx, &numy, params).unwrap();
+
+            assert_eq!(cs.which_is_unsatisfied().unwrap(), "on curve check");
+        }
+    }
+
+    #[test]
+    fn test_doubling_order_2() {
+        let params = &JubjubBls12::new();
+
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let x = AllocatedNum::alloc(cs.namespace(|| "x"), || {
+            Ok(Fr::zero())
+        }).unwrap();
+        let y = AllocatedNum::alloc(cs.namespace(|| "y"), || {
+            Ok(Fr::zero())
+        }).unwrap();
+
+        let p = MontgomeryPoint {
+            x: x,
+            y: y
+        };
+
+        assert!(p.double(&mut cs, params).is_err());
+    }
+
+    #[test]
+    fn test_montgomery_addition() {
+        let params = &JubjubBls12::new();
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..100 {
+            let p1 = loop {
+                let x: Fr = rng.gen();
+                let s: bool = rng.gen();
+
+                if let Some(p) = montgomery::Point::<Bls12, _>::get_for_x(x, s, params) {
+                    break p;
+                }
+            };
+
+            let p2 = loop {
+                let x: Fr = rng.gen();
+                let s: bool = rng.gen();
+
+                if let Some(p) = montgomery::Point::<Bls12, _>::get_for_x(x, s, params) {
+                    break p;
+                }
+            };
+
+            let p3 = p1.add(&p2, params);
+
+            let (x0, y0) = p1.into_xy().unwrap();
+            let (x1, y1) = p2.into_xy().unwrap();
+            let (x2, y2) = p3.into_xy().unwrap();
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let num_x0 = AllocatedNum::alloc(cs.namespace(|| "x0"), || {
+                Ok(x0)
+            }).unwrap();
+            let num_y0 = AllocatedNum::alloc(cs.namespace(|| "y0"), || {
+                Ok(y0)
+            }).unwrap();
+
+            let num_x1 = AllocatedNum::alloc(cs.namespace(|| "x1"), || {
+                Ok(x1)
+            }).unwrap();
+            let num_y1 = AllocatedNum::alloc(cs.namespace(|| "y1"), || {
+                Ok(y1)
+            }).unwrap();
+
+            let p1 = MontgomeryPoint {
+                x: num_x0,
+                y: num_y0
+            };
+
+            let p2 = MontgomeryPoint {
+                x: num_x1,
+                y: num_y1
+            };
+
+            let p3 = p1.add(cs.namespace(|| "addition"), &p2, params).unwrap();
+
+            assert!(cs.is_satisfied());
+
+            assert!(p3.x.get_value().unwrap() == x2);
+            assert!(p3.y.get_value().unwrap() == y2);
+
+            cs.set("addition/yprime/num", rng.gen());
+            assert_eq!(cs.which_is_unsatisfied(), Some("addition/evaluate yprime"));
+            cs.set("addition/yprime/num", y2);
+            assert!(cs.is_satisfied());
+
+            cs.set("addition/xprime/num", rng.gen());
+            assert_eq!(cs.which_is_unsatisfied(), Some("addition/evaluate xprime"));
+            cs.set("addition/xprime/num", x2);
+            assert!(cs.is_satisfied());
+
+            cs.set("addition/lambda/num", rng.gen());
+            assert_eq!(cs.which_is_unsatisfied(), Some("addition/evaluate lambda"));
+        }
+    }
+
+    #[test]
+    fn test_montgomery_checked_addition() {
+        let params = &JubjubBls12::new();
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..10 {
+            let p1 = loop {
+                let x: Fr = rng.gen();
+                let s: bool = rng.gen();
+
+                if let Some(p) = montgomery::Point::<Bls12, _>::get_for_x(x, s, params) {
+                    break p;
+                }
+            };
+
+            let p2 = loop {
+                let x: Fr = rng.gen();
+                let s: bool = rng.gen();
+
+                if let Some(p) = montgomery::Point::<Bls12, _>::get_for_x(x, s, params) {
+                    break p;
+                }
+            };
+
+            let (x0, y0) = p1.into_xy().unwrap();
+            let (x1, y1) = p2.into_xy().unwrap();
+            let (x2, y2) = p1.add(&p2, params).into_xy().unwrap();
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let p1 = MontgomeryPoint {
+                x: AllocatedNum::alloc(cs.namespace(|| "x0"), || Ok(x0)).unwrap(),
+                y: AllocatedNum::alloc(cs.namespace(|| "y0"), || Ok(y0)).unwrap()
+            };
+
+            let p2 = MontgomeryPoint {
+                x: AllocatedNum::alloc(cs.namespace(|| "x1"), || Ok(x1)).unwrap(),
+                y: AllocatedNum::alloc(cs.namespace(|| "y1"), || Ok(y1)).unwrap()
+            };
+
+            let p3 = p1.checked_add(cs.namespace(|| "checked addition"), &p2, params).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(cs.num_constraints(), 4);
+            assert_eq!(p3.x.get_value().unwrap(), x2);
+            assert_eq!(p3.y.get_value().unwrap(), y2);
+
+            // Giving both inputs the same x-coordinate
+            // violates the nonzero difference constraint.
+            cs.set("x1/num", x0);
+            assert_eq!(
+                cs.which_is_unsatisfied(),
+                Some("checked addition/x inequality/inequality constraint")
+            );
+
+            // Coincident points cannot be witnessed at all.
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let p1 = MontgomeryPoint {
+                x: AllocatedNum::alloc(cs.namespace(|| "x0"), || Ok(x0)).unwrap(),
+                y: AllocatedNum::alloc(cs.namespace(|| "y0"), || Ok(y0)).unwrap()
+            };
+
+            assert!(p1.checked_add(cs.namespace(|| "checked addition"), &p1, params).is_err());
+        }
+    }
+
+    #[test]
+    fn test_montgomery_doubling() {
+        let params = &JubjubBls12::new();
+        let rng = &mut XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..100 {
+            let p = loop {
+                let x: Fr = rng.gen();
+                let s: bool = rng.gen();
+
+                if let Some(p) = montgomery::Point::<Bls12, _>::get_for_x(x, s, params) {
+                    break p;
+                }
+            };
+
+            let p2 = p.double(params);
+
+            let (x0, y0) = p.into_xy().unwrap();
+            let (x1, y1) = p2.into_xy().unwrap();
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let x = AllocatedNum::alloc(cs.namespace(|| "x"), || {
+                Ok(x0)
+            }).unwrap();
+            let y = AllocatedNum::alloc(cs.namespace(|| "y"), || {
+                Ok(y0)
+            }).unwrap();
+
+            let p = MontgomeryPoint {
+                x: x,
+                y: y
+            };
+
+            let p2 = p.double(cs.namespace(|| "doubling"), params).unwrap();
+
+            assert!(cs.is_satisfied());
+
+            assert!(p2.x.get_value().unwrap() == x1);
+            assert!(p2.y.get_value().unwrap() == y1);
+
+            cs.set("doubling/yprime/num", rng.gen());
+            assert_eq!(cs.which_is_unsatisfied(), Some("doubling/evaluate yprime"));
+            cs.set("doubling/yprime/num", y1);
+            assert!(cs.is_satisfied());
+
+            cs.set("doubling/xprime/num", rng.gen());
+            assert_eq!(cs.which_is_unsatisfied(), Some("doubling/evaluate xprime"));
+            cs.set("doubling/xprime/num", x1);
+            assert!(cs.is_satisfied());
+
+            cs.set("doubling/lambda/num", rng.gen());
+            assert_eq!(cs.which_is_unsatisfied(), Some("doubling/evaluate lambda"));
+        }
+    }
+}
@@ -1,7 +1,13 @@
 use pairing::{Engine, Field};
 use super::*;
+use ::jubjub::{
+    JubjubEngine,
+    PrimeOrder,
+    edwards,
+    montgomery
+};
 use super::num::AllocatedNum;
-use super::boolean::Boolean;
+use super::boolean::{Boolean, AllocatedBit};
 use bellman::{
     ConstraintSystem,
     LinearCombination
@@ -118,6 +124,187 @@ pub fn lookup3_xy<E: Engine, CS, Var: Copy>(
     Ok((res_x, res_y))
 }
 
+/// Performs a window table lookup with any number of
+/// bits, generalizing `lookup3_xy`. `bits` is in
+/// little-endian order and `coords` has an entry for
+/// each of the `2^n` patterns. The lookup costs the
+/// two coordinate constraints plus one for each
+/// product of two or more of the bits after the first.
+pub fn lookup_n<E: Engine, CS, Var: Copy>(
+    mut cs: CS,
+    bits: &[Boolean<Var>],
+    coords: &[(E::Fr, E::Fr)]
+) -> Result<(AllocatedNum<E, Var>, AllocatedNum<E, Var>), SynthesisError>
+    where CS: ConstraintSystem<E, Variable=Var>
+{
+    assert!(bits.len() > 0);
+    assert_eq!(coords.len(), 1 << bits.len());
+
+    // Calculate the index into `coords`
+    let i = bits.iter().rev().fold(Some(0), |acc, bit| {
+        match (acc, bit.get_value()) {
+            (Some(acc), Some(bit)) => Some(acc * 2 + (bit as usize)),
+            _ => None
+        }
+    });
+
+    // Allocate the x-coordinate resulting from the lookup
+    let res_x = AllocatedNum::alloc(
+        cs.namespace(|| "x"),
+        || {
+            Ok(coords[*i.get()?].0)
+        }
+    )?;
+
+    // Allocate the y-coordinate resulting from the lookup
+    let res_y = AllocatedNum::alloc(
+        cs.namespace(|| "y"),
+        || {
+            Ok(coords[*i.get()?].1)
+        }
+    )?;
+
+    // Compute the coefficients for the lookup constraints
+    let mut x_coeffs = vec![E::Fr::zero(); coords.len()];
+    let mut y_coeffs = vec![E::Fr::zero(); coords.len()];
+    synth::<E, _>(bits.len(), coords.iter().map(|c| &c.0), &mut x_coeffs);
+    synth::<E, _>(bits.len(), coords.iter().map(|c| &c.1), &mut y_coeffs);
+
+    // The product of each subset of the bits after the
+    // first, indexed by the subset, built up from the
+    // subset without its lowest bit.
+    let mut products = vec![Boolean::constant(true)];
+    for subset in 1..(1 << (bits.len() - 1)) {
+        let lowest = (subset as usize).trailing_zeros() as usize;
+        let rest = subset & (subset - 1);
+
+        let product = if rest == 0 {
+            bits[lowest + 1].clone()
+        } else {
+            Boolean::and(
+                cs.namespace(|| format!("product {}", subset)),
+                &products[rest],
+                &bits[lowest + 1]
+            )?
+        };
+
+        products.push(product);
+    }
+
+    let one = cs.one();
+
+    for &(annotation, res, ref coeffs) in &[
+        ("x-coordinate lookup", &res_x, &x_coeffs),
+        ("y-coordinate lookup", &res_y, &y_coeffs)
+    ]
+    {
+        // res = A + bits[0] * B, where A and B collect the
+        // terms without and with the first bit.
+        let mut a = LinearCombination::<Var, E>::zero() + res.get_variable();
+        let mut b = LinearCombination::<Var, E>::zero();
+        for (subset, product) in products.iter().enumerate() {
+            a = a - &product.lc::<E>(one, coeffs[subset << 1]);
+            b = b + &product.lc::<E>(one, coeffs[(subset << 1) | 1]);
+        }
+
+        cs.enforce(
+            || annotation,
+            b,
+            LinearCombination::<Var, E>::zero() + &bits[0].lc::<E>(one, E::Fr::one()),
+            a
+        );
+    }
+
+    Ok((res_x, res_y))
+}
+
+/// Performs a 2-bit lookup into a table of constant
+/// booleans. `bits` is in little-endian order.
+pub fn lookup2_bool<E: Engine, CS, Var: Copy>(
+    mut cs: CS,
+    bits: &[Boolean<Var>; 2],
+    table: &[bool; 4]
+) -> Result<Boolean<Var>, SynthesisError>
+    where CS: ConstraintSystem<E, Variable=Var>
+{
+    // Calculate the index into `table`
+    let i =
+    match (bits[0].get_value(), bits[1].get_value()) {
+        (Some(a_value), Some(b_value)) => {
+            let mut tmp = 0;
+            if a_value {
+                tmp += 1;
+            }
+            if b_value {
+                tmp += 2;
+            }
+            Some(tmp)
+        },
+        _ => None
+    };
+
+    // Constant selectors need no constraints.
+    match (&bits[0], &bits[1]) {
+        (&Boolean::Constant(_), &Boolean::Constant(_)) => {
+            return Ok(Boolean::constant(table[i.unwrap()]));
+        },
+        _ => {}
+    }
+
+    let res = AllocatedBit::alloc(
+        cs.namespace(|| "result"),
+        i.map(|i| table[i])
+    )?;
+
+    // Compute the coefficients for the lookup constraint
+    let table: Vec<E::Fr> = table.iter().map(|&b| {
+        if b { E::Fr::one() } else { E::Fr::zero() }
+    }).collect();
+    let mut coeffs = [E::Fr::zero(); 4];
+    synth::<E, _>(2, table.iter(), &mut coeffs);
+
+    let one = cs.one();
+
+    cs.enforce(
+        || "lookup",
+        LinearCombination::<Var, E>::zero() + (coeffs[0b01], one)
+                                            + &bits[1].lc::<E>(one, coeffs[0b11]),
+        LinearCombination::<Var, E>::zero() + &bits[0].lc::<E>(one, E::Fr::one()),
+        LinearCombination::<Var, E>::zero() + res.get_variable()
+                                            - (coeffs[0b00], one)
+                                            - &bits[1].lc::<E>(one, coeffs[0b10])
+    );
+
+    Ok(Boolean::from(res))
+}
+
+/// Computes the table that
+/// `lookup3_xy_with_conditional_negation` expects for
+/// window `window_index` of `generator`: the Montgomery
+/// coordinates of 1, 2, 3 and 4 times the generator
+/// scaled by 16^window_index. This is the layout of
+/// the Pedersen hash window tables.
+pub fn precompute_window<E: JubjubEngine>(
+    generator: &edwards::Point<E, PrimeOrder>,
+    window_index: usize,
+    params: &E::Params
+) -> Vec<(E::Fr, E::Fr)>
+{
+    let mut base = montgomery::Point::from_edwards(generator, params);
+    for _ in 0..(window_index * 4) {
+        base = base.double(params);
+    }
+
+    let mut coords = vec![];
+    let mut g = base.clone();
+    for _ in 0..4 {
+        coords.push(g.into_xy().expect("cannot produce O"));
+        g = g.add(&base, params);
+    }
+
+    coords
+}
+
 /// Performs a 3-bit window table lookup, where
 /// one of the bits is a sign bit.
 pub fn lookup3_xy_with_conditional_negation<E: Engine, CS, Var: Copy>(
@@ -195,6 +382,25 @@ pub fn lookup3_xy_with_conditional_negation<E: Engine, CS, Var: Copy>(
     Ok((res_x, final_y))
 }
 
+/// Same as `lookup3_xy_with_conditional_negation`, but
+/// performs the lookup in a namespace naming the Pedersen
+/// hash segment and window it belongs to, so that failing
+/// constraints can be located.
+pub fn lookup3_xy_with_conditional_negation_indexed<E: Engine, CS, Var: Copy>(
+    mut cs: CS,
+    (segment_i, window_i): (usize, usize),
+    bits: &[Boolean<Var>],
+    coords: &[(E::Fr, E::Fr)]
+) -> Result<(AllocatedNum<E, Var>, AllocatedNum<E, Var>), SynthesisError>
+    where CS: ConstraintSystem<E, Variable=Var>
+{
+    lookup3_xy_with_conditional_negation(
+        cs.namespace(|| format!("segment {}, window {}", segment_i, window_i)),
+        bits,
+        coords
+    )
+}
+
 #[cfg(test)]
 mod test {
     use rand::{SeedableRng, Rand, Rng, XorShiftRng};
@@ -202,6 +408,8 @@ mod test {
     use ::circuit::test::*;
     use ::circuit::boolean::{Boolean, AllocatedBit};
     use pairing::bls12_381::{Bls12, Fr};
+    use ::jubjub::{JubjubBls12, JubjubParams};
+    use ::jubjub::fs::FsRepr;
 
     #[test]
     fn test_lookup3_xy() {
@@ -307,4 +515,184 @@ mod test {
             assert_eq!(acc, constants[b]);
         }
     }
+
+    #[test]
+    fn test_lookup_n() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..10 {
+            let points: Vec<(Fr, Fr)> = (0..4).map(|_| (rng.gen(), rng.gen())).collect();
+
+            for index in 0..4 {
+                let mut cs = TestConstraintSystem::<Bls12>::new();
+
+                let bits: Vec<_> = (0..2).map(|j| {
+                    Boolean::from(
+                        AllocatedBit::alloc(cs.namespace(|| format!("bit {}", j)), Some((index >> j) & 1 == 1)).unwrap()
+                    )
+                }).collect();
+
+                let res = lookup_n(cs.namespace(|| "lookup"), &bits, &points).unwrap();
+
+                assert!(cs.is_satisfied());
+                assert_eq!(cs.num_constraints(), 2 + 2);
+                assert_eq!(res.0.get_value().unwrap(), points[index].0);
+                assert_eq!(res.1.get_value().unwrap(), points[index].1);
+
+                // Any other entry breaks the lookup.
+                cs.set("lookup/x/num", points[(index + 1) % 4].0);
+                assert_eq!(cs.which_is_unsatisfied(), Some("lookup/x-coordinate lookup"));
+            }
+        }
+
+        // Wider windows agree with lookup3_xy, and the
+        // constant bits are folded in.
+        for n in 1..6 {
+            let points: Vec<(Fr, Fr)> = (0..(1 << n)).map(|_| (rng.gen(), rng.gen())).collect();
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let index = rng.gen::<usize>() % (1 << n);
+            let bits: Vec<_> = (0..n).map(|j| {
+                let b = (index >> j) & 1 == 1;
+                if j == 1 {
+                    Boolean::constant(b)
+                } else {
+                    Boolean::from(
+                        AllocatedBit::alloc(cs.namespace(|| format!("bit {}", j)), Some(b)).unwrap()
+                    )
+                }
+            }).collect();
+
+            let before = cs.num_constraints();
+            let res = lookup_n(cs.namespace(|| "lookup"), &bits, &points).unwrap();
+            let cost = cs.num_constraints() - before;
+
+            assert!(cs.is_satisfied());
+            assert_eq!(res.0.get_value().unwrap(), points[index].0);
+            assert_eq!(res.1.get_value().unwrap(), points[index].1);
+
+            if n == 3 {
+                let before = cs.num_constraints();
+                let expected = lookup3_xy(cs.namespace(|| "lookup3"), &bits, &points).unwrap();
+                assert_eq!(expected.0.get_value(), res.0.get_value());
+                assert_eq!(expected.1.get_value(), res.1.get_value());
+                assert_eq!(cs.num_constraints() - before, cost);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lookup2_bool() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..100 {
+            let table = [rng.gen(), rng.gen(), rng.gen(), rng.gen()];
+
+            for index in 0..4 {
+                let a_val = index & 1 == 1;
+                let b_val = index & 2 == 2;
+
+                let mut cs = TestConstraintSystem::<Bls12>::new();
+
+                let a = Boolean::from(
+                    AllocatedBit::alloc(cs.namespace(|| "a"), Some(a_val)).unwrap()
+                );
+                let b = Boolean::from(
+                    AllocatedBit::alloc(cs.namespace(|| "b"), Some(b_val)).unwrap()
+                );
+
+                let res = lookup2_bool(cs.namespace(|| "lookup"), &[a.clone(), b.clone()], &table).unwrap();
+
+                assert!(cs.is_satisfied());
+                assert_eq!(cs.num_constraints(), 4);
+                assert_eq!(res.get_value().unwrap(), table[index]);
+
+                // Mixed and constant selectors
+                let res = lookup2_bool(cs.namespace(|| "lookup mixed"), &[Boolean::constant(a_val), b.not().not()], &table).unwrap();
+                assert!(cs.is_satisfied());
+                assert_eq!(res.get_value().unwrap(), table[index]);
+
+                let res = lookup2_bool(cs.namespace(|| "lookup constant"), &[Boolean::constant(a_val), Boolean::constant(b_val)], &table).unwrap();
+                assert_eq!(res.get_value().unwrap(), table[index]);
+                assert_eq!(cs.num_constraints(), 6);
+
+                // Flipping the result breaks the lookup constraint.
+                let r = cs.get("lookup/result/boolean");
+                cs.set("lookup/result/boolean", if r == Fr::zero() { Fr::one() } else { Fr::zero() });
+                assert_eq!(cs.which_is_unsatisfied(), Some("lookup/lookup"));
+            }
+        }
+    }
+
+    #[test]
+    fn test_precompute_window() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let params = &JubjubBls12::new();
+
+        // The Pedersen hash window tables are laid out
+        // the same way.
+        let generator = &params.pedersen_hash_generators()[1];
+        for window in &[0, 1, 5, 61] {
+            assert_eq!(
+                precompute_window(generator, *window, params),
+                params.pedersen_circuit_generators()[1][*window]
+            );
+        }
+
+        // A table for an arbitrary point selects the
+        // right multiple of it.
+        let generator = edwards::Point::<Bls12, _>::rand(&mut rng, params).mul_by_cofactor(params);
+        for window in 0..4 {
+            let coords = precompute_window(&generator, window, params);
+
+            for i in 0..8 {
+                let mut cs = TestConstraintSystem::<Bls12>::new();
+
+                let bits: Vec<_> = (0..3).map(|j| {
+                    Boolean::from(
+                        AllocatedBit::alloc(cs.namespace(|| format!("bit {}", j)), Some((i >> j) & 1 == 1)).unwrap()
+                    )
+                }).collect();
+
+                let res = lookup3_xy_with_conditional_negation(&mut cs, &bits, &coords).unwrap();
+                assert!(cs.is_satisfied());
+
+                let scalar = ((i & 3) + 1) << (4 * window);
+                let mut expected = generator.mul(FsRepr::from(scalar as u64), params);
+                if i & 4 == 4 {
+                    expected = expected.negate();
+                }
+                let expected = montgomery::Point::from_edwards(&expected, params).into_xy().unwrap();
+
+                assert_eq!(res.0.get_value().unwrap(), expected.0);
+                assert_eq!(res.1.get_value().unwrap(), expected.1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lookup3_xy_with_conditional_negation_indexed() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let bits: Vec<_> = (0..3).map(|i| {
+            Boolean::from(
+                AllocatedBit::alloc(cs.namespace(|| format!("bit {}", i)), Some(rng.gen())).unwrap()
+            )
+        }).collect();
+
+        let points: Vec<(Fr, Fr)> = (0..4).map(|_| (rng.gen(), rng.gen())).collect();
+
+        let res = lookup3_xy_with_conditional_negation_indexed(&mut cs, (1, 2), &bits, &points).unwrap();
+
+        assert!(cs.is_satisfied());
+
+        let x = cs.get("segment 1, window 2/x/num");
+        assert!(x == res.0.get_value().unwrap());
+
+        cs.set("segment 1, window 2/x/num", rng.gen());
+        assert_eq!(cs.which_is_unsatisfied(), Some("segment 1, window 2/x-coordinate lookup"));
+    }
 }
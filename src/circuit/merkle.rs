@@ -0,0 +1,204 @@
+use pairing::{
+    Field,
+    PrimeField
+};
+use super::*;
+use super::ecc::EdwardsPoint;
+use super::num::AllocatedNum;
+use super::boolean::Boolean;
+use super::pedersen_hash::{
+    pedersen_hash,
+    Personalization
+};
+use ::jubjub::*;
+use bellman::{
+    ConstraintSystem,
+    LinearCombination
+};
+
+/// Computes the root of a Pedersen hash Merkle tree given
+/// the little-endian bits of a leaf and its authentication
+/// path. Each entry of `path` holds a boolean which is true
+/// if the current node is the right child, along with the
+/// sibling node, represented by its x-coordinate. Returns
+/// the x-coordinate of the root.
+pub fn compute_root<E: JubjubEngine, CS, Var: Copy>(
+    mut cs: CS,
+    leaf: &[Boolean<Var>],
+    path: &[(Boolean<Var>, EdwardsPoint<E, Var>)],
+    params: &E::Params
+) -> Result<AllocatedNum<E, Var>, SynthesisError>
+    where CS: ConstraintSystem<E, Variable=Var>
+{
+    assert!(leaf.len() <= E::Fr::NUM_BITS as usize);
+
+    // Pack the leaf into a single field element.
+    let mut value = Some(E::Fr::zero());
+    let mut lc = LinearCombination::<Var, E>::zero();
+    let mut coeff = E::Fr::one();
+    let one = cs.one();
+
+    for bit in leaf {
+        lc = lc + &bit.lc(one, coeff);
+
+        match bit.get_value() {
+            Some(true) => { value.as_mut().map(|v| v.add_assign(&coeff)); },
+            Some(false) => {},
+            None => { value = None }
+        }
+
+        coeff.double();
+    }
+
+    let mut cur = AllocatedNum::alloc(cs.namespace(|| "leaf"), || {
+        Ok(*value.get()?)
+    })?;
+
+    cs.enforce(
+        || "leaf packing",
+        lc,
+        LinearCombination::zero() + one,
+        LinearCombination::zero() + cur.get_variable()
+    );
+
+    for (i, &(ref cur_is_right, ref sibling)) in path.iter().enumerate() {
+        let mut cs = cs.namespace(|| format!("merkle tree hash {}", i));
+
+        // Swap the current node and its sibling if the
+        // current node is the right child.
+        let (xl, xr) = AllocatedNum::conditionally_reverse(
+            cs.namespace(|| "conditional reversal of preimage"),
+            &cur,
+            &sibling.x,
+            cur_is_right
+        )?;
+
+        let mut preimage = xl.into_bits_strict(cs.namespace(|| "xl into bits"))?;
+        preimage.reverse();
+
+        let mut xr_bits = xr.into_bits_strict(cs.namespace(|| "xr into bits"))?;
+        xr_bits.reverse();
+
+        preimage.extend(xr_bits);
+
+        cur = pedersen_hash(
+            cs.namespace(|| "computation of pedersen hash"),
+            Personalization::MerkleTree(i),
+            &preimage,
+            params
+        )?.into_num();
+    }
+
+    Ok(cur)
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{SeedableRng, Rng, XorShiftRng};
+    use super::*;
+    use ::circuit::test::*;
+    use ::circuit::boolean::{Boolean, AllocatedBit};
+    use pairing::bls12_381::{Bls12, Fr};
+    use pairing::BitIterator;
+
+    fn le_bits(value: Fr) -> Vec<bool> {
+        let mut bits = BitIterator::new(value.into_repr()).collect::<Vec<_>>();
+        bits.reverse();
+        bits.truncate(Fr::NUM_BITS as usize);
+
+        bits
+    }
+
+    fn native_root(
+        leaf: Fr,
+        path: &[(bool, edwards::Point<Bls12, Unknown>)],
+        params: &JubjubBls12
+    ) -> Fr
+    {
+        let mut cur = leaf;
+
+        for (i, &(cur_is_right, ref sibling)) in path.iter().enumerate() {
+            let sibling = sibling.into_xy().0;
+            let (xl, xr) = if cur_is_right { (sibling, cur) } else { (cur, sibling) };
+
+            cur = ::pedersen_hash::pedersen_hash::<Bls12, _>(
+                Personalization::MerkleTree(i),
+                le_bits(xl).into_iter().chain(le_bits(xr).into_iter()),
+                params
+            ).into_xy().0;
+        }
+
+        cur
+    }
+
+    fn circuit_root(
+        leaf: Fr,
+        path: &[(bool, edwards::Point<Bls12, Unknown>)],
+        params: &JubjubBls12
+    ) -> (TestConstraintSystem<Bls12>, Fr)
+    {
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let leaf_bits: Vec<Boolean<_>> = le_bits(leaf).into_iter().enumerate().map(|(i, b)| {
+            Boolean::from(
+                AllocatedBit::alloc(cs.namespace(|| format!("leaf bit {}", i)), Some(b)).unwrap()
+            )
+        }).collect();
+
+        let path: Vec<_> = path.iter().enumerate().map(|(i, &(cur_is_right, ref sibling))| {
+            let (x, y) = sibling.into_xy();
+
+            let sibling = EdwardsPoint {
+                x: AllocatedNum::alloc(cs.namespace(|| format!("sibling x {}", i)), || Ok(x)).unwrap(),
+                y: AllocatedNum::alloc(cs.namespace(|| format!("sibling y {}", i)), || Ok(y)).unwrap()
+            };
+
+            let cur_is_right = Boolean::from(
+                AllocatedBit::alloc(cs.namespace(|| format!("position bit {}", i)), Some(cur_is_right)).unwrap()
+            );
+
+            (cur_is_right, sibling)
+        }).collect();
+
+        let root = compute_root(
+            cs.namespace(|| "merkle root"),
+            &leaf_bits,
+            &path,
+            params
+        ).unwrap().get_value().unwrap();
+
+        (cs, root)
+    }
+
+    #[test]
+    fn test_compute_root() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let params = &JubjubBls12::new();
+
+        for _ in 0..5 {
+            let leaf: Fr = rng.gen();
+            let mut path: Vec<_> = (0..4).map(|_| {
+                (rng.gen(), edwards::Point::<Bls12, _>::rand(&mut rng, params))
+            }).collect();
+
+            let (mut cs, root) = circuit_root(leaf, &path, params);
+
+            assert!(cs.is_satisfied());
+            assert_eq!(root, native_root(leaf, &path, params));
+
+            // Flipping a position bit in the witness alone
+            // leaves the constraints unsatisfied.
+            let bit = cs.get("position bit 0/boolean");
+            cs.set("position bit 0/boolean", if bit == Fr::zero() { Fr::one() } else { Fr::zero() });
+            assert!(!cs.is_satisfied());
+
+            // Flipping it honestly changes the root.
+            path[0].0 = !path[0].0;
+            let (cs, flipped_root) = circuit_root(leaf, &path, params);
+
+            assert!(cs.is_satisfied());
+            assert!(flipped_root != root);
+            assert_eq!(flipped_root, native_root(leaf, &path, params));
+        }
+    }
+}
@@ -4,10 +4,18 @@ pub mod test;
 pub mod boolean;
 pub mod uint32;
 pub mod blake2s;
+pub mod sha256;
 pub mod num;
-pub mod mont;
+pub mod ecc;
 pub mod lookup;
 pub mod pedersen_hash;
+pub mod merkle;
+pub mod poseidon;
+pub mod value_commitment;
+pub mod sapling;
+
+#[cfg(feature = "profiler")]
+pub mod profiler;
 
 use bellman::SynthesisError;
 
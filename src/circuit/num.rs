@@ -2,12 +2,14 @@ use pairing::{
     Engine,
     Field,
     PrimeField,
+    PrimeFieldRepr,
     BitIterator
 };
 
 use bellman::{
     SynthesisError,
     ConstraintSystem,
+    PublicConstraintSystem,
     LinearCombination
 };
 
@@ -20,6 +22,56 @@ use super::boolean::{
    AllocatedBit
 };
 
+/// Returns the number of constraints that
+/// `AllocatedNum::into_bits` adds: one per bit,
+/// plus the packing constraint.
+pub fn into_bits_cost<E: Engine>() -> usize {
+    E::Fr::NUM_BITS as usize + 1
+}
+
+/// Packs `bits`, in little-endian order, into as few
+/// field elements as possible, `E::Fr::CAPACITY` bits
+/// at a time. Each costs a single constraint.
+pub fn pack_into_nums<E: Engine, CS, Var: Copy>(
+    mut cs: CS,
+    bits: &[Boolean<Var>]
+) -> Result<Vec<AllocatedNum<E, Var>>, SynthesisError>
+    where CS: ConstraintSystem<E, Variable=Var>
+{
+    let mut nums = vec![];
+    let one = cs.one();
+
+    for (i, chunk) in bits.chunks(E::Fr::CAPACITY as usize).enumerate() {
+        let num = bits_to_num_lc(one, chunk);
+
+        nums.push(num.into_allocated_num(cs.namespace(|| format!("chunk {}", i)))?);
+    }
+
+    Ok(nums)
+}
+
+/// Returns the linear combination that packs `bits`,
+/// least significant first, without adding any
+/// constraints. Pass `bits.iter().rev()` to pack
+/// big-endian bits such as those of `into_bits`.
+pub fn bits_to_num_lc<'a, E: Engine, Var: Copy + 'a, I>(
+    one: Var,
+    bits: I
+) -> Num<E, Var>
+    where I: IntoIterator<Item=&'a Boolean<Var>>
+{
+    let mut num = Num::zero();
+    let mut coeff = E::Fr::one();
+
+    for bit in bits {
+        num = num.add_bool_with_coeff(one, bit, coeff);
+
+        coeff.double();
+    }
+
+    num
+}
+
 pub struct AllocatedNum<E: Engine, Var> {
     value: Option<E::Fr>,
     variable: Var
@@ -57,6 +109,115 @@ impl<E: Engine, Var: Copy> AllocatedNum<E, Var> {
         })
     }
 
+    /// Same as `alloc`, but allocates the number as a
+    /// public input.
+    pub fn alloc_input<CS, F>(
+        mut cs: CS,
+        value: F,
+    ) -> Result<Self, SynthesisError>
+        where CS: PublicConstraintSystem<E, Variable=Var>,
+              F: FnOnce() -> Result<E::Fr, SynthesisError>
+    {
+        let mut new_value = None;
+        let var = cs.alloc_input(|| "input num", || {
+            let tmp = value()?;
+
+            new_value = Some(tmp);
+
+            Ok(tmp)
+        })?;
+
+        Ok(AllocatedNum {
+            value: new_value,
+            variable: var
+        })
+    }
+
+    /// Wraps an existing variable as an `AllocatedNum`
+    /// without allocating anything. The caller is
+    /// responsible for any constraints on it.
+    pub fn from_variable(variable: Var, value: Option<E::Fr>) -> Self {
+        AllocatedNum {
+            value: value,
+            variable: variable
+        }
+    }
+
+    /// Reuses the variable of `bit` as a number, which
+    /// is zero or one. This costs nothing, as the bit
+    /// is already constrained to be boolean.
+    pub fn from_bit(bit: &AllocatedBit<Var>) -> Self {
+        Self::from_variable(
+            bit.get_variable(),
+            bit.get_value().map(|b| if b { E::Fr::one() } else { E::Fr::zero() })
+        )
+    }
+
+    /// Allocates the constant one, at the cost of
+    /// one constraint.
+    pub fn one<CS>(
+        cs: CS
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        Self::alloc_constant(cs, E::Fr::one())
+    }
+
+    /// Allocates the constant zero, at the cost of
+    /// one constraint.
+    pub fn zero<CS>(
+        cs: CS
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        Self::alloc_constant(cs, E::Fr::zero())
+    }
+
+    /// Allocates a number which is constrained to
+    /// equal the constant `value`.
+    pub fn alloc_constant<CS>(
+        mut cs: CS,
+        value: E::Fr
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        let var = cs.alloc(|| "constant num", || Ok(value))?;
+
+        // Constrain: var * 1 = value
+        let one = cs.one();
+        cs.enforce(
+            || "constant constraint",
+            LinearCombination::zero() + var,
+            LinearCombination::zero() + one,
+            LinearCombination::<Var, E>::zero() + (value, one)
+        );
+
+        Ok(AllocatedNum {
+            value: Some(value),
+            variable: var
+        })
+    }
+
+    /// Allocates the constant `value`, like
+    /// `alloc_constant`. Returns an error if `value`
+    /// is not in the field.
+    pub fn from_u64<CS>(
+        cs: CS,
+        value: u64
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        // There is no more specific error to return
+        // for a field too small to hold the value.
+        let value = E::Fr::from_repr(value.into())
+                          .map_err(|_| SynthesisError::AssignmentMissing)?;
+
+        Self::alloc_constant(cs, value)
+    }
+
+    /// Same as `into_bits`, but also enforces that the
+    /// bits are the canonical representation of the
+    /// number, i.e. that they are less than the modulus.
     pub fn into_bits_strict<CS>(
         &self,
         mut cs: CS
@@ -69,6 +230,31 @@ impl<E: Engine, Var: Copy> AllocatedNum<E, Var> {
         Ok(bits)
     }
 
+    /// Same as `into_bits_strict`, but for a number known
+    /// to fit in `num_bits` bits: only that many bits are
+    /// allocated, in big-endian order, and packing them
+    /// proves the bound, so no field check is needed.
+    pub fn into_bits_bounded<CS>(
+        &self,
+        cs: CS,
+        num_bits: usize
+    ) -> Result<Vec<Boolean<Var>>, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        let mut bits = self.assert_fits_in_bits(cs, num_bits)?;
+        bits.reverse();
+
+        Ok(bits)
+    }
+
+    /// Decomposes this number into `E::Fr::NUM_BITS`
+    /// boolean bits, in big-endian order, which are
+    /// constrained to sum to the number.
+    ///
+    /// The representation is not unique: small values
+    /// also have the representation of the value plus
+    /// the modulus. Use `into_bits_strict` when the
+    /// canonical representation is needed.
     pub fn into_bits<CS>(
         &self,
         mut cs: CS
@@ -101,6 +287,90 @@ impl<E: Engine, Var: Copy> AllocatedNum<E, Var> {
             }
         };
 
+        let mut bits = vec![];
+        for (i, b) in bit_values.into_iter().enumerate() {
+            bits.push(Boolean::from(AllocatedBit::alloc(
+                cs.namespace(|| format!("bit {}", i)),
+                b
+            )?));
+        }
+
+        let one = cs.one();
+        let lc = bits_to_num_lc::<E, _, _>(one, bits.iter().rev()).lc(E::Fr::one());
+
+        cs.enforce(
+            || "unpacking constraint",
+            LinearCombination::zero(),
+            LinearCombination::zero(),
+            lc - self.variable
+        );
+
+        Ok(bits)
+    }
+
+    /// Same as `into_bits`, but returns the bits in
+    /// little-endian order.
+    pub fn into_bits_le<CS>(
+        &self,
+        cs: CS
+    ) -> Result<Vec<Boolean<Var>>, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        let mut bits = self.into_bits(cs)?;
+        bits.reverse();
+
+        Ok(bits)
+    }
+
+    /// Returns the least significant bit of the canonical
+    /// representation of this number.
+    ///
+    /// Witnessing just `b` and `q` with `self = 2q + b` is
+    /// not sound: 2 is invertible in the field, so every
+    /// number can be written that way with either parity.
+    /// The parity is only defined once `q` is range checked,
+    /// which costs as much as a strict decomposition.
+    pub fn lsb<CS>(
+        &self,
+        cs: CS
+    ) -> Result<Boolean<Var>, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        let bits = self.into_bits_strict(cs)?;
+
+        Ok(bits.last().unwrap().clone())
+    }
+
+    /// Enforces that this number fits in `num_bits` bits
+    /// by unpacking it into exactly that many booleans,
+    /// which are returned in little-endian order. If the
+    /// value does not fit, the unpacking constraint is
+    /// unsatisfiable.
+    pub fn assert_fits_in_bits<CS>(
+        &self,
+        mut cs: CS,
+        num_bits: usize
+    ) -> Result<Vec<Boolean<Var>>, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        // Any more than this and the packing could wrap
+        // around the modulus.
+        assert!(num_bits <= E::Fr::CAPACITY as usize);
+
+        let bit_values = match self.value {
+            Some(value) => {
+                let repr = value.into_repr();
+                let limbs = repr.as_ref();
+
+                (0..num_bits).map(|i| {
+                    Some((limbs[i / 64] >> (i % 64)) & 1 == 1)
+                }).collect::<Vec<_>>()
+            },
+            None => {
+                vec![None; num_bits]
+            }
+        };
+
         let mut bits = vec![];
         for (i, b) in bit_values.into_iter().enumerate() {
             bits.push(AllocatedBit::alloc(
@@ -112,7 +382,7 @@ impl<E: Engine, Var: Copy> AllocatedNum<E, Var> {
         let mut lc = LinearCombination::zero();
         let mut coeff = E::Fr::one();
 
-        for bit in bits.iter().rev() {
+        for bit in bits.iter() {
             lc = lc + (coeff, bit.get_variable());
 
             coeff.double();
@@ -130,76 +400,125 @@ impl<E: Engine, Var: Copy> AllocatedNum<E, Var> {
         Ok(bits.into_iter().map(|b| Boolean::from(b)).collect())
     }
 
-    pub fn from_bits_strict<CS>(
+    /// Returns a boolean that is true iff `a < b`.
+    ///
+    /// Both `a` and `b` must be known to fit in `num_bits`
+    /// bits, e.g. via `assert_fits_in_bits`; otherwise the
+    /// result is meaningless. This works by decomposing
+    /// `b - a - 1 + 2^num_bits` into `num_bits + 1` bits
+    /// and returning the top one.
+    pub fn less_than<CS>(
         mut cs: CS,
-        bits: &[Boolean<Var>]
-    ) -> Result<Self, SynthesisError>
+        a: &Self,
+        b: &Self,
+        num_bits: usize
+    ) -> Result<Boolean<Var>, SynthesisError>
         where CS: ConstraintSystem<E, Variable=Var>
     {
-        assert_eq!(bits.len(), E::Fr::NUM_BITS as usize);
+        // The decomposition must not wrap around the modulus.
+        assert!(num_bits < E::Fr::CAPACITY as usize);
 
-        Boolean::enforce_in_field::<_, _, E::Fr>(&mut cs, bits)?;
+        // 2^num_bits - 1
+        let mut offset = E::Fr::one();
+        for _ in 0..num_bits {
+            offset.double();
+        }
+        offset.sub_assign(&E::Fr::one());
 
-        let one = cs.one();
-        let mut lc = LinearCombination::<Var, E>::zero();
-        let mut coeff = E::Fr::one();
-        let mut value = Some(E::Fr::zero());
-        for bit in bits.iter().rev() {
-            match bit {
-                &Boolean::Constant(false) => {},
-                &Boolean::Constant(true) => {
-                    value.as_mut().map(|value| value.add_assign(&coeff));
-
-                    lc = lc + (coeff, one);
-                },
-                &Boolean::Is(ref bit) => {
-                    match bit.get_value() {
-                        Some(bit) => {
-                            if bit {
-                                value.as_mut().map(|value| value.add_assign(&coeff));
-                            }
-                        },
-                        None => {
-                            value = None;
-                        }
-                    }
+        let bit_values = match (a.value, b.value) {
+            (Some(a), Some(b)) => {
+                let mut tmp = b;
+                tmp.sub_assign(&a);
+                tmp.add_assign(&offset);
 
-                    lc = lc + (coeff, bit.get_variable());
-                },
-                &Boolean::Not(ref bit) => {
-                    match bit.get_value() {
-                        Some(bit) => {
-                            if !bit {
-                                value.as_mut().map(|value| value.add_assign(&coeff));
-                            }
-                        },
-                        None => {
-                            value = None;
-                        }
-                    }
+                let repr = tmp.into_repr();
+                let limbs = repr.as_ref();
 
-                    lc = lc + (coeff, one) - (coeff, bit.get_variable());
-                }
+                (0..(num_bits + 1)).map(|i| {
+                    Some((limbs[i / 64] >> (i % 64)) & 1 == 1)
+                }).collect::<Vec<_>>()
+            },
+            _ => {
+                vec![None; num_bits + 1]
             }
+        };
 
-            coeff.double();
+        let mut bits = vec![];
+        for (i, b) in bit_values.into_iter().enumerate() {
+            bits.push(AllocatedBit::alloc(
+                cs.namespace(|| format!("bit {}", i)),
+                b
+            )?);
         }
 
-        let num = Self::alloc(&mut cs, || value.get().map(|v| *v))?;
+        let one = cs.one();
+        let mut lc = LinearCombination::zero();
+        let mut coeff = E::Fr::one();
+
+        for bit in bits.iter() {
+            lc = lc + (coeff, bit.get_variable());
+
+            coeff.double();
+        }
 
-        lc = lc - num.get_variable();
+        lc = lc - b.variable + a.variable - (offset, one);
 
         cs.enforce(
-            || "packing constraint",
+            || "comparison constraint",
             LinearCombination::zero(),
             LinearCombination::zero(),
             lc
         );
 
-        Ok(num)
+        Ok(Boolean::from(bits.pop().unwrap()))
     }
 
-    pub fn mul<CS>(
+    /// Packs big-endian `bits` into a number, enforcing
+    /// that they are less than the modulus. Constant bits
+    /// are folded into the packing constraint, so an
+    /// all-constant input costs just that one linear
+    /// constraint. Returns an error unless exactly
+    /// `NUM_BITS` bits are given.
+    pub fn from_bits_strict<CS>(
+        mut cs: CS,
+        bits: &[Boolean<Var>]
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        if bits.len() != E::Fr::NUM_BITS as usize {
+            // There is no more specific error to return.
+            return Err(SynthesisError::AssignmentMissing);
+        }
+
+        Boolean::enforce_in_field::<_, _, E::Fr>(&mut cs, bits)?;
+
+        Self::from_bits(cs, bits)
+    }
+
+    /// Packs big-endian `bits` into a number using only
+    /// the packing constraint. Returns an error if more
+    /// than `NUM_BITS` bits are given. Nothing checks that
+    /// they are less than the modulus, so with exactly
+    /// `NUM_BITS` bits two encodings can pack to the same
+    /// number. Use `from_bits_strict` unless the bits are
+    /// known to be canonical.
+    pub fn from_bits<CS>(
+        cs: CS,
+        bits: &[Boolean<Var>]
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        if bits.len() > E::Fr::NUM_BITS as usize {
+            // There is no more specific error to return.
+            return Err(SynthesisError::AssignmentMissing);
+        }
+
+        let one = cs.one();
+
+        bits_to_num_lc(one, bits.iter().rev()).into_allocated_num(cs)
+    }
+
+    pub fn add<CS>(
         &self,
         mut cs: CS,
         other: &Self
@@ -208,20 +527,21 @@ impl<E: Engine, Var: Copy> AllocatedNum<E, Var> {
     {
         let mut value = None;
 
-        let var = cs.alloc(|| "product num", || {
+        let var = cs.alloc(|| "sum num", || {
             let mut tmp = *self.value.get()?;
-            tmp.mul_assign(other.value.get()?);
+            tmp.add_assign(other.value.get()?);
 
             value = Some(tmp);
 
             Ok(tmp)
         })?;
 
-        // Constrain: a * b = ab
+        // Constrain: (a + b) * 1 = a + b
+        let one = cs.one();
         cs.enforce(
-            || "multiplication constraint",
-            LinearCombination::zero() + self.variable,
-            LinearCombination::zero() + other.variable,
+            || "addition constraint",
+            LinearCombination::zero() + self.variable + other.variable,
+            LinearCombination::zero() + one,
             LinearCombination::zero() + var
         );
 
@@ -231,28 +551,94 @@ impl<E: Engine, Var: Copy> AllocatedNum<E, Var> {
         })
     }
 
-    pub fn square<CS>(
+    /// Adds `other` to this number and enforces that the
+    /// sum fits in `num_bits` bits, so that an overflowing
+    /// total is unsatisfiable. The inputs are not range
+    /// checked; they are assumed to fit already.
+    pub fn add_checked<CS>(
         &self,
-        mut cs: CS
+        mut cs: CS,
+        other: &Self,
+        num_bits: usize
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        let sum = self.add(cs.namespace(|| "addition"), other)?;
+        sum.assert_fits_in_bits(cs.namespace(|| "range check"), num_bits)?;
+
+        Ok(sum)
+    }
+
+    /// Enforces that this number is `m` times a quotient
+    /// of at most `num_bits` bits, and returns the
+    /// quotient. Every number is a multiple of a nonzero
+    /// `m` in the field, so the bound is what makes this
+    /// divisibility over the integers.
+    pub fn assert_multiple_of<CS>(
+        &self,
+        mut cs: CS,
+        m: E::Fr,
+        num_bits: usize
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        // The product of m and the quotient must not
+        // wrap around the modulus.
+        assert!(m.into_repr().num_bits() as usize + num_bits <= E::Fr::CAPACITY as usize);
+
+        let m_inv = match m.inverse() {
+            Some(m_inv) => m_inv,
+            None => return Err(SynthesisError::AssignmentMissing)
+        };
+
+        let q = Self::alloc(cs.namespace(|| "quotient"), || {
+            let mut tmp = *self.value.get()?;
+            tmp.mul_assign(&m_inv);
+
+            Ok(tmp)
+        })?;
+
+        // Constrain: q * m = a
+        let one = cs.one();
+        cs.enforce(
+            || "multiple constraint",
+            LinearCombination::zero() + q.variable,
+            LinearCombination::<Var, E>::zero() + (m, one),
+            LinearCombination::zero() + self.variable
+        );
+
+        q.assert_fits_in_bits(cs.namespace(|| "range check"), num_bits)?;
+
+        Ok(q)
+    }
+
+    /// Adds a constant to this number. The constant is
+    /// folded into the linear combination against the
+    /// "one" variable, so it is never allocated.
+    pub fn add_constant<CS>(
+        &self,
+        mut cs: CS,
+        c: E::Fr
     ) -> Result<Self, SynthesisError>
         where CS: ConstraintSystem<E, Variable=Var>
     {
         let mut value = None;
 
-        let var = cs.alloc(|| "squared num", || {
+        let var = cs.alloc(|| "sum num", || {
             let mut tmp = *self.value.get()?;
-            tmp.square();
+            tmp.add_assign(&c);
 
             value = Some(tmp);
 
             Ok(tmp)
         })?;
 
-        // Constrain: a * a = aa
+        // Constrain: (a + c) * 1 = a + c
+        let one = cs.one();
         cs.enforce(
-            || "squaring constraint",
-            LinearCombination::zero() + self.variable,
-            LinearCombination::zero() + self.variable,
+            || "addition constraint",
+            LinearCombination::zero() + self.variable + (c, one),
+            LinearCombination::zero() + one,
             LinearCombination::zero() + var
         );
 
@@ -262,192 +648,1787 @@ impl<E: Engine, Var: Copy> AllocatedNum<E, Var> {
         })
     }
 
-    pub fn assert_nonzero<CS>(
+    /// Multiplies this number by a constant. The constant
+    /// scales the linear combination, so this costs no
+    /// multiplication.
+    pub fn mul_by_constant<CS>(
         &self,
-        mut cs: CS
-    ) -> Result<(), SynthesisError>
+        mut cs: CS,
+        c: E::Fr
+    ) -> Result<Self, SynthesisError>
         where CS: ConstraintSystem<E, Variable=Var>
     {
-        let inv = cs.alloc(|| "ephemeral inverse", || {
-            let tmp = *self.value.get()?;
-            
-            if tmp.is_zero() {
-                Err(SynthesisError::AssignmentMissing)
-            } else {
-                Ok(tmp.inverse().unwrap())
-            }
+        let mut value = None;
+
+        let var = cs.alloc(|| "scaled num", || {
+            let mut tmp = *self.value.get()?;
+            tmp.mul_assign(&c);
+
+            value = Some(tmp);
+
+            Ok(tmp)
         })?;
 
-        // Constrain a * inv = 1, which is only valid
-        // iff a has a multiplicative inverse, untrue
-        // for zero.
+        // Constrain: (c * a) * 1 = r
         let one = cs.one();
         cs.enforce(
-            || "nonzero assertion constraint",
-            LinearCombination::zero() + self.variable,
-            LinearCombination::zero() + inv,
-            LinearCombination::zero() + one
+            || "scaling constraint",
+            LinearCombination::<Var, E>::zero() + (c, self.variable),
+            LinearCombination::zero() + one,
+            LinearCombination::zero() + var
         );
 
-        Ok(())
+        Ok(AllocatedNum {
+            value: value,
+            variable: var
+        })
     }
 
-    /// Takes two allocated numbers (a, b) and returns
-    /// (b, a) if the condition is true, and (a, b)
-    /// otherwise.
-    pub fn conditionally_reverse<CS>(
+    /// Returns `coeff` times this number as a linear
+    /// combination, for use in other constraints.
+    pub fn lc(&self, coeff: E::Fr) -> LinearCombination<Var, E> {
+        LinearCombination::<Var, E>::zero() + (coeff, self.variable)
+    }
+
+    pub fn negate<CS>(
+        &self,
+        mut cs: CS
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        let mut value = None;
+
+        let var = cs.alloc(|| "negated num", || {
+            let mut tmp = *self.value.get()?;
+            tmp.negate();
+
+            value = Some(tmp);
+
+            Ok(tmp)
+        })?;
+
+        // Constrain: (a + r) * 1 = 0
+        let one = cs.one();
+        cs.enforce(
+            || "negation constraint",
+            LinearCombination::zero() + self.variable + var,
+            LinearCombination::zero() + one,
+            LinearCombination::zero()
+        );
+
+        Ok(AllocatedNum {
+            value: value,
+            variable: var
+        })
+    }
+
+    /// Sums all of the numbers in `nums` using a single
+    /// constraint, regardless of how many there are.
+    pub fn sum<CS>(
         mut cs: CS,
-        a: &Self,
-        b: &Self,
-        condition: &Boolean<Var>
-    ) -> Result<(Self, Self), SynthesisError>
+        nums: &[Self]
+    ) -> Result<Self, SynthesisError>
         where CS: ConstraintSystem<E, Variable=Var>
     {
-        let c = Self::alloc(
-            cs.namespace(|| "conditional reversal result 1"),
-            || {
-                if *condition.get_value().get()? {
-                    Ok(*b.value.get()?)
-                } else {
-                    Ok(*a.value.get()?)
-                }
+        if nums.len() == 0 {
+            return Self::alloc_constant(cs, E::Fr::zero());
+        }
+
+        let mut value = None;
+
+        let var = cs.alloc(|| "sum num", || {
+            let mut tmp = E::Fr::zero();
+
+            for num in nums {
+                tmp.add_assign(num.value.get()?);
             }
-        )?;
 
+            value = Some(tmp);
+
+            Ok(tmp)
+        })?;
+
+        let mut lc = LinearCombination::zero();
+        for num in nums {
+            lc = lc + num.variable;
+        }
+
+        // Constrain: (a_0 + a_1 + ...) * 1 = sum
         let one = cs.one();
         cs.enforce(
-            || "first conditional reversal",
-            LinearCombination::zero() + a.variable - b.variable,
-            condition.lc(one, E::Fr::one()),
-            LinearCombination::zero() + a.variable - c.variable
+            || "summation constraint",
+            lc,
+            LinearCombination::zero() + one,
+            LinearCombination::zero() + var
         );
 
-        let d = Self::alloc(
-            cs.namespace(|| "conditional reversal result 2"),
-            || {
-                if *condition.get_value().get()? {
-                    Ok(*a.value.get()?)
-                } else {
-                    Ok(*b.value.get()?)
+        Ok(AllocatedNum {
+            value: value,
+            variable: var
+        })
+    }
+
+    pub fn sub<CS>(
+        &self,
+        mut cs: CS,
+        other: &Self
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        let mut value = None;
+
+        let var = cs.alloc(|| "difference num", || {
+            let mut tmp = *self.value.get()?;
+            tmp.sub_assign(other.value.get()?);
+
+            value = Some(tmp);
+
+            Ok(tmp)
+        })?;
+
+        // Constrain: (a - b) * 1 = a - b
+        let one = cs.one();
+        cs.enforce(
+            || "subtraction constraint",
+            LinearCombination::zero() + self.variable - other.variable,
+            LinearCombination::zero() + one,
+            LinearCombination::zero() + var
+        );
+
+        Ok(AllocatedNum {
+            value: value,
+            variable: var
+        })
+    }
+
+    pub fn mul<CS>(
+        &self,
+        mut cs: CS,
+        other: &Self
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        let mut value = None;
+
+        let var = cs.alloc(|| "product num", || {
+            let mut tmp = *self.value.get()?;
+            tmp.mul_assign(other.value.get()?);
+
+            value = Some(tmp);
+
+            Ok(tmp)
+        })?;
+
+        // Constrain: a * b = ab
+        cs.enforce(
+            || "multiplication constraint",
+            LinearCombination::zero() + self.variable,
+            LinearCombination::zero() + other.variable,
+            LinearCombination::zero() + var
+        );
+
+        Ok(AllocatedNum {
+            value: value,
+            variable: var
+        })
+    }
+
+    /// Computes `self * b + c` with a single
+    /// constraint, as the addition can be folded
+    /// into the output of the multiplication.
+    pub fn mul_add<CS>(
+        &self,
+        mut cs: CS,
+        b: &Self,
+        c: &Self
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        let mut value = None;
+
+        let var = cs.alloc(|| "mul add num", || {
+            let mut tmp = *self.value.get()?;
+            tmp.mul_assign(b.value.get()?);
+            tmp.add_assign(c.value.get()?);
+
+            value = Some(tmp);
+
+            Ok(tmp)
+        })?;
+
+        // Constrain: a * b = r - c
+        cs.enforce(
+            || "mul add constraint",
+            LinearCombination::zero() + self.variable,
+            LinearCombination::zero() + b.variable,
+            LinearCombination::zero() + var - c.variable
+        );
+
+        Ok(AllocatedNum {
+            value: value,
+            variable: var
+        })
+    }
+
+    /// Divides this number by `other`, which must be
+    /// nonzero.
+    pub fn div<CS>(
+        &self,
+        mut cs: CS,
+        other: &Self
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        let mut value = None;
+
+        let var = cs.alloc(|| "quotient num", || {
+            let mut tmp = *self.value.get()?;
+
+            match other.value.get()?.inverse() {
+                Some(inv) => {
+                    tmp.mul_assign(&inv);
+
+                    value = Some(tmp);
+
+                    Ok(tmp)
+                },
+                None => {
+                    Err(SynthesisError::AssignmentMissing)
                 }
             }
-        )?;
+        })?;
 
+        // Constrain: q * b = a
         cs.enforce(
-            || "second conditional reversal",
-            LinearCombination::zero() + b.variable - a.variable,
-            condition.lc(one, E::Fr::one()),
-            LinearCombination::zero() + b.variable - d.variable
+            || "division constraint",
+            LinearCombination::zero() + var,
+            LinearCombination::zero() + other.variable,
+            LinearCombination::zero() + self.variable
         );
 
-        Ok((c, d))
+        Ok(AllocatedNum {
+            value: value,
+            variable: var
+        })
     }
 
-    pub fn conditionally_negate<CS>(
+    /// Computes the inner product of `a` and `b`, which
+    /// must have the same length.
+    pub fn inner_product<CS>(
+        mut cs: CS,
+        a: &[Self],
+        b: &[Self]
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        assert_eq!(a.len(), b.len());
+
+        let mut products = vec![];
+        for (i, (a, b)) in a.iter().zip(b.iter()).enumerate() {
+            products.push(a.mul(cs.namespace(|| format!("product {}", i)), b)?);
+        }
+
+        Self::sum(cs.namespace(|| "sum of products"), &products)
+    }
+
+    pub fn square<CS>(
+        &self,
+        mut cs: CS
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        let mut value = None;
+
+        let var = cs.alloc(|| "squared num", || {
+            let mut tmp = *self.value.get()?;
+            tmp.square();
+
+            value = Some(tmp);
+
+            Ok(tmp)
+        })?;
+
+        // Constrain: a * a = aa
+        cs.enforce(
+            || "squaring constraint",
+            LinearCombination::zero() + self.variable,
+            LinearCombination::zero() + self.variable,
+            LinearCombination::zero() + var
+        );
+
+        Ok(AllocatedNum {
+            value: value,
+            variable: var
+        })
+    }
+
+    pub fn cube<CS>(
+        &self,
+        mut cs: CS
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        let square = self.square(cs.namespace(|| "square"))?;
+
+        square.mul(cs.namespace(|| "cube"), self)
+    }
+
+    /// Raises this number to a constant power using
+    /// square-and-multiply.
+    pub fn pow_constant<CS>(
         &self,
         mut cs: CS,
-        condition: &Boolean<Var>
+        exp: u64
     ) -> Result<Self, SynthesisError>
         where CS: ConstraintSystem<E, Variable=Var>
     {
-        let r = Self::alloc(
-            cs.namespace(|| "conditional negation result"),
-            || {
-                let mut tmp = *self.value.get()?;
-                if *condition.get_value().get()? {
-                    tmp.negate();
-                }
-                Ok(tmp)
+        match exp {
+            0 => return Self::alloc_constant(cs, E::Fr::one()),
+            1 => return Ok(self.clone()),
+            _ => {}
+        }
+
+        let mut result = self.clone();
+
+        // Skip the leading one; it is accounted for
+        // by starting with `self`.
+        let num_bits = 64 - exp.leading_zeros() as usize;
+        for i in (0..(num_bits - 1)).rev() {
+            result = result.square(cs.namespace(|| format!("square {}", i)))?;
+
+            if (exp >> i) & 1 == 1 {
+                result = result.mul(cs.namespace(|| format!("multiply {}", i)), self)?;
             }
-        )?;
+        }
+
+        Ok(result)
+    }
+
+    /// Allocates the multiplicative inverse of this
+    /// number, which must be nonzero.
+    pub fn inverse<CS>(
+        &self,
+        mut cs: CS
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        let mut value = None;
+
+        let var = cs.alloc(|| "inverse num", || {
+            let tmp = *self.value.get()?;
+
+            match tmp.inverse() {
+                Some(tmp) => {
+                    value = Some(tmp);
+
+                    Ok(tmp)
+                },
+                None => {
+                    Err(SynthesisError::AssignmentMissing)
+                }
+            }
+        })?;
+
+        // Constrain: a * inv = 1
+        let one = cs.one();
+        cs.enforce(
+            || "inversion constraint",
+            LinearCombination::zero() + self.variable,
+            LinearCombination::zero() + var,
+            LinearCombination::zero() + one
+        );
+
+        Ok(AllocatedNum {
+            value: value,
+            variable: var
+        })
+    }
+
+    /// Inverts every number in `nums` using Montgomery's
+    /// trick: only the product of all of the numbers is
+    /// inverted, and the individual inverses are recovered
+    /// with multiplications. Every number must be nonzero.
+    pub fn batch_invert<CS>(
+        mut cs: CS,
+        nums: &[Self]
+    ) -> Result<Vec<Self>, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        if nums.len() == 0 {
+            return Ok(vec![]);
+        }
+
+        // prefixes[i] = nums[0] * ... * nums[i]
+        let mut prefixes = vec![nums[0].clone()];
+        for (i, num) in nums.iter().enumerate().skip(1) {
+            let tmp = prefixes[i - 1].mul(
+                cs.namespace(|| format!("prefix product {}", i)),
+                num
+            )?;
+            prefixes.push(tmp);
+        }
+
+        // Invert the product of all of the numbers
+        let mut acc = prefixes[nums.len() - 1].inverse(
+            cs.namespace(|| "inversion of product")
+        )?;
+
+        let mut result = vec![];
+        for i in (1..nums.len()).rev() {
+            // (nums[0] * ... * nums[i])^-1 * (nums[0] * ... * nums[i - 1])
+            result.push(acc.mul(
+                cs.namespace(|| format!("inverse {}", i)),
+                &prefixes[i - 1]
+            )?);
+
+            // (nums[0] * ... * nums[i - 1])^-1
+            acc = acc.mul(
+                cs.namespace(|| format!("accumulator {}", i)),
+                &nums[i]
+            )?;
+        }
+        result.push(acc);
+        result.reverse();
+
+        Ok(result)
+    }
+
+    pub fn assert_nonzero<CS>(
+        &self,
+        cs: CS
+    ) -> Result<(), SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        self.assert_nonzero_with_inverse(cs).map(|_| ())
+    }
+
+    /// Same as `assert_nonzero`, but returns the inverse
+    /// used to prove it, for gadgets that go on to divide
+    /// by this number.
+    pub fn assert_nonzero_with_inverse<CS>(
+        &self,
+        mut cs: CS
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        let mut value = None;
+
+        let inv = cs.alloc(|| "ephemeral inverse", || {
+            let tmp = *self.value.get()?;
+            
+            if tmp.is_zero() {
+                Err(SynthesisError::AssignmentMissing)
+            } else {
+                let tmp = tmp.inverse().unwrap();
+                value = Some(tmp);
+
+                Ok(tmp)
+            }
+        })?;
+
+        // Constrain a * inv = 1, which is only valid
+        // iff a has a multiplicative inverse, untrue
+        // for zero.
+        let one = cs.one();
+        cs.enforce(
+            || "nonzero assertion constraint",
+            LinearCombination::zero() + self.variable,
+            LinearCombination::zero() + inv,
+            LinearCombination::zero() + one
+        );
+
+        Ok(AllocatedNum {
+            value: value,
+            variable: inv
+        })
+    }
+
+    /// Enforces that this number is zero or one, and
+    /// returns it as a boolean sharing the same variable.
+    pub fn assert_boolean<CS>(
+        &self,
+        cs: CS
+    ) -> Result<Boolean<Var>, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        let value = match self.value {
+            Some(ref v) if v.is_zero() => Some(false),
+            Some(ref v) if *v == E::Fr::one() => Some(true),
+            _ => None
+        };
+
+        let bit = AllocatedBit::from_variable(cs, self.variable, value)?;
+
+        Ok(Boolean::Is(bit))
+    }
+
+    /// Enforces that this number is equal to `other`.
+    pub fn assert_equal<CS>(
+        &self,
+        mut cs: CS,
+        other: &Self
+    ) -> Result<(), SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        // Constrain: (a - b) * 1 = 0
+        let one = cs.one();
+        cs.enforce(
+            || "equality constraint",
+            LinearCombination::zero() + self.variable - other.variable,
+            LinearCombination::zero() + one,
+            LinearCombination::zero()
+        );
+
+        Ok(())
+    }
+
+    /// Enforces that this number is not equal to `other`
+    /// by witnessing the inverse of their difference.
+    pub fn enforce_not_equal<CS>(
+        &self,
+        mut cs: CS,
+        other: &Self
+    ) -> Result<(), SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        let inv = cs.alloc(|| "ephemeral inverse", || {
+            let mut tmp = *self.value.get()?;
+            tmp.sub_assign(other.value.get()?);
+
+            if tmp.is_zero() {
+                Err(SynthesisError::AssignmentMissing)
+            } else {
+                Ok(tmp.inverse().unwrap())
+            }
+        })?;
+
+        // Constrain (a - b) * inv = 1, which is only
+        // valid iff a - b is nonzero.
+        let one = cs.one();
+        cs.enforce(
+            || "inequality constraint",
+            LinearCombination::zero() + self.variable - other.variable,
+            LinearCombination::zero() + inv,
+            LinearCombination::zero() + one
+        );
+
+        Ok(())
+    }
+
+    /// Returns a boolean that is true iff this number
+    /// is equal to the constant `c`, by checking that
+    /// `self - c` is zero. Costs three constraints.
+    pub fn equals_constant<CS>(
+        &self,
+        mut cs: CS,
+        c: E::Fr
+    ) -> Result<Boolean<Var>, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        let diff = self.value.map(|mut tmp| {
+            tmp.sub_assign(&c);
+            tmp
+        });
+
+        let eq = AllocatedBit::alloc(
+            cs.namespace(|| "is equal"),
+            diff.map(|d| d.is_zero())
+        )?;
+
+        // The inverse of the difference, or zero if
+        // there is none.
+        let inv = cs.alloc(|| "ephemeral inverse", || {
+            Ok(diff.get()?.inverse().unwrap_or(E::Fr::zero()))
+        })?;
+
+        let one = cs.one();
+        let mut neg_c = c;
+        neg_c.negate();
+
+        // Constrain: (a - c) * inv = 1 - eq
+        // so eq must be set if a - c is zero.
+        cs.enforce(
+            || "inverse constraint",
+            LinearCombination::zero() + self.variable + (neg_c, one),
+            LinearCombination::zero() + inv,
+            LinearCombination::zero() + one - eq.get_variable()
+        );
+
+        // Constrain: (a - c) * eq = 0
+        // so eq must be unset if a - c is nonzero.
+        cs.enforce(
+            || "equality constraint",
+            LinearCombination::zero() + self.variable + (neg_c, one),
+            LinearCombination::zero() + eq.get_variable(),
+            LinearCombination::zero()
+        );
+
+        Ok(Boolean::from(eq))
+    }
+
+    /// Enforces that this number is one of the `allowed`
+    /// values, by constraining the product of the
+    /// differences to be zero. Costs one constraint per
+    /// allowed value after the first, and at least one.
+    pub fn assert_in_set<CS>(
+        &self,
+        mut cs: CS,
+        allowed: &[E::Fr]
+    ) -> Result<(), SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        assert!(allowed.len() > 0);
+
+        let one = cs.one();
+
+        // The running product of (self - v) over the
+        // values so far; the first factor needs no
+        // allocation.
+        let mut product: Option<Self> = None;
+
+        for (i, v) in allowed.iter().enumerate().skip(1) {
+            let (prev_lc, prev_value) = match product {
+                Some(ref product) => {
+                    (LinearCombination::<Var, E>::zero() + product.variable, product.value)
+                },
+                None => {
+                    let mut neg_v = allowed[0];
+                    neg_v.negate();
+
+                    (
+                        LinearCombination::zero() + self.variable + (neg_v, one),
+                        self.value.map(|mut x| {
+                            x.sub_assign(&allowed[0]);
+                            x
+                        })
+                    )
+                }
+            };
+
+            let mut neg_v = *v;
+            neg_v.negate();
+            let factor_lc = LinearCombination::zero() + self.variable + (neg_v, one);
+
+            if i == allowed.len() - 1 {
+                cs.enforce(
+                    || "set membership constraint",
+                    prev_lc,
+                    factor_lc,
+                    LinearCombination::zero()
+                );
+
+                return Ok(());
+            }
+
+            let next = Self::alloc(
+                cs.namespace(|| format!("product {}", i)),
+                || {
+                    let mut tmp = *self.value.get()?;
+                    tmp.sub_assign(v);
+                    tmp.mul_assign(prev_value.get()?);
+
+                    Ok(tmp)
+                }
+            )?;
+
+            cs.enforce(
+                || format!("product {} computation", i),
+                prev_lc,
+                factor_lc,
+                LinearCombination::zero() + next.variable
+            );
+
+            product = Some(next);
+        }
+
+        // Only a single value is allowed.
+        let mut neg_v = allowed[0];
+        neg_v.negate();
+        cs.enforce(
+            || "set membership constraint",
+            LinearCombination::zero() + self.variable + (neg_v, one),
+            LinearCombination::zero() + one,
+            LinearCombination::zero()
+        );
+
+        Ok(())
+    }
+
+    /// Takes two allocated numbers (a, b) and returns
+    /// (b, a) if the condition is true, and (a, b)
+    /// otherwise.
+    pub fn conditionally_reverse<CS>(
+        mut cs: CS,
+        a: &Self,
+        b: &Self,
+        condition: &Boolean<Var>
+    ) -> Result<(Self, Self), SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        match condition.get_constant_value() {
+            Some(true) => return Ok((b.clone(), a.clone())),
+            Some(false) => return Ok((a.clone(), b.clone())),
+            None => {}
+        }
+
+        let c = Self::alloc(
+            cs.namespace(|| "conditional reversal result 1"),
+            || {
+                if *condition.get_value().get()? {
+                    Ok(*b.value.get()?)
+                } else {
+                    Ok(*a.value.get()?)
+                }
+            }
+        )?;
+
+        let one = cs.one();
+        cs.enforce(
+            || "first conditional reversal",
+            LinearCombination::zero() + a.variable - b.variable,
+            condition.lc(one, E::Fr::one()),
+            LinearCombination::zero() + a.variable - c.variable
+        );
+
+        let d = Self::alloc(
+            cs.namespace(|| "conditional reversal result 2"),
+            || {
+                if *condition.get_value().get()? {
+                    Ok(*a.value.get()?)
+                } else {
+                    Ok(*b.value.get()?)
+                }
+            }
+        )?;
+
+        cs.enforce(
+            || "second conditional reversal",
+            LinearCombination::zero() + b.variable - a.variable,
+            condition.lc(one, E::Fr::one()),
+            LinearCombination::zero() + b.variable - d.variable
+        );
+
+        Ok((c, d))
+    }
+
+    /// Takes two allocated numbers (a, b) and returns
+    /// a if the condition is true, and b otherwise.
+    /// Constant conditions do not add any constraints.
+    pub fn conditionally_select<CS>(
+        mut cs: CS,
+        a: &Self,
+        b: &Self,
+        condition: &Boolean<Var>
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        match condition {
+            &Boolean::Constant(true) => return Ok(a.clone()),
+            &Boolean::Constant(false) => return Ok(b.clone()),
+            _ => {}
+        }
+
+        let c = Self::alloc(
+            cs.namespace(|| "conditional select result"),
+            || {
+                if *condition.get_value().get()? {
+                    Ok(*a.value.get()?)
+                } else {
+                    Ok(*b.value.get()?)
+                }
+            }
+        )?;
+
+        // (a - b) * condition = c - b
+        let one = cs.one();
+        cs.enforce(
+            || "conditional select",
+            LinearCombination::zero() + a.variable - b.variable,
+            condition.lc(one, E::Fr::one()),
+            LinearCombination::zero() + c.variable - b.variable
+        );
+
+        Ok(c)
+    }
+
+    /// Selects all of `a` if the condition is true, and
+    /// all of `b` otherwise, at the cost of one
+    /// constraint per element.
+    pub fn conditionally_select_multiple<CS>(
+        mut cs: CS,
+        a: &[Self],
+        b: &[Self],
+        condition: &Boolean<Var>
+    ) -> Result<Vec<Self>, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        assert_eq!(a.len(), b.len());
+
+        a.iter().zip(b.iter()).enumerate().map(|(i, (a, b))| {
+            Self::conditionally_select(
+                cs.namespace(|| format!("element {}", i)),
+                a,
+                b,
+                condition
+            )
+        }).collect()
+    }
+
+    /// Returns `table[index]`, where `index` is given by
+    /// `index_bits` in little-endian order, using a tree of
+    /// conditional selections. The table must have exactly
+    /// `2^index_bits.len()` entries.
+    pub fn select_from_table<CS>(
+        mut cs: CS,
+        index_bits: &[Boolean<Var>],
+        table: &[E::Fr]
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        assert_eq!(table.len(), 1 << index_bits.len());
+
+        if index_bits.len() == 0 {
+            return Self::alloc_constant(cs.namespace(|| "entry"), table[0]);
+        }
+
+        // The first level selects between constants, which
+        // needs no allocations for the inputs.
+        let one = cs.one();
+        let bit = &index_bits[0];
+        let mut level = vec![];
+        for (i, pair) in table.chunks(2).enumerate() {
+            let r = Self::alloc(
+                cs.namespace(|| format!("level 0, entry {}", i)),
+                || {
+                    if *bit.get_value().get()? {
+                        Ok(pair[1])
+                    } else {
+                        Ok(pair[0])
+                    }
+                }
+            )?;
+
+            // (t1 - t0) * bit = r - t0
+            let mut diff = pair[1];
+            diff.sub_assign(&pair[0]);
+            cs.enforce(
+                || format!("level 0, entry {} selection", i),
+                LinearCombination::<Var, E>::zero() + (diff, one),
+                bit.lc(one, E::Fr::one()),
+                LinearCombination::<Var, E>::zero() + r.variable - (pair[0], one)
+            );
+
+            level.push(r);
+        }
+
+        for (depth, bit) in index_bits.iter().enumerate().skip(1) {
+            let mut next = vec![];
+            for (i, pair) in level.chunks(2).enumerate() {
+                next.push(Self::conditionally_select(
+                    cs.namespace(|| format!("level {}, entry {}", depth, i)),
+                    &pair[1],
+                    &pair[0],
+                    bit
+                )?);
+            }
+
+            level = next;
+        }
+
+        Ok(level.pop().unwrap())
+    }
+
+    /// Returns `self + addend` if the condition is true,
+    /// and `self` otherwise.
+    pub fn conditionally_add<CS>(
+        &self,
+        mut cs: CS,
+        addend: &Self,
+        condition: &Boolean<Var>
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        match condition {
+            &Boolean::Constant(true) => return self.add(cs, addend),
+            &Boolean::Constant(false) => return Ok(self.clone()),
+            _ => {}
+        }
+
+        let r = Self::alloc(
+            cs.namespace(|| "conditional addition result"),
+            || {
+                let mut tmp = *self.value.get()?;
+                if *condition.get_value().get()? {
+                    tmp.add_assign(addend.value.get()?);
+                }
+                Ok(tmp)
+            }
+        )?;
+
+        // addend * condition = r - self
+        let one = cs.one();
+        cs.enforce(
+            || "conditional addition",
+            LinearCombination::zero() + addend.variable,
+            condition.lc(one, E::Fr::one()),
+            LinearCombination::zero() + r.variable - self.variable
+        );
+
+        Ok(r)
+    }
+
+    pub fn conditionally_negate<CS>(
+        &self,
+        mut cs: CS,
+        condition: &Boolean<Var>
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        if condition.get_constant_value() == Some(false) {
+            return Ok(self.clone());
+        }
+
+        let r = Self::alloc(
+            cs.namespace(|| "conditional negation result"),
+            || {
+                let mut tmp = *self.value.get()?;
+                if *condition.get_value().get()? {
+                    tmp.negate();
+                }
+                Ok(tmp)
+            }
+        )?;
+
+        // (1-c)(x) + (c)(-x) = r
+        // x - 2cx = r
+        // (2x) * (c) = x - r
+
+        let one = cs.one();
+        cs.enforce(
+            || "conditional negation",
+            LinearCombination::zero() + self.variable + self.variable,
+            condition.lc(one, E::Fr::one()),
+            LinearCombination::zero() + self.variable - r.variable
+        );
+
+        Ok(r)
+    }
+
+    /// Same as `conditionally_negate`, for a condition
+    /// that is an allocated bit.
+    pub fn conditionally_negate_bit<CS>(
+        &self,
+        cs: CS,
+        condition: &AllocatedBit<Var>
+    ) -> Result<Self, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        self.conditionally_negate(cs, &Boolean::Is(condition.clone()))
+    }
+
+    pub fn get_value(&self) -> Option<E::Fr> {
+        self.value
+    }
+
+    /// Replaces the cached value without touching the
+    /// assignment in the constraint system. Gadgets built
+    /// on this number then derive their witnesses from the
+    /// wrong value, which tests use to check that the
+    /// constraints catch it.
+    #[cfg(test)]
+    pub fn override_value(&mut self, value: Option<E::Fr>) {
+        self.value = value;
+    }
+
+    pub fn get_variable(&self) -> Var {
+        self.variable
+    }
+}
+
+/// A linear combination of allocated numbers, booleans
+/// and constants, along with its value, which has not
+/// been enforced to equal any variable.
+pub struct Num<E: Engine, Var> {
+    value: Option<E::Fr>,
+    lc: LinearCombination<Var, E>
+}
+
+impl<E: Engine, Var: Copy> From<AllocatedNum<E, Var>> for Num<E, Var> {
+    fn from(num: AllocatedNum<E, Var>) -> Num<E, Var> {
+        Num {
+            value: num.value,
+            lc: LinearCombination::<Var, E>::zero() + num.variable
+        }
+    }
+}
+
+impl<E: Engine, Var: Copy> Num<E, Var> {
+    pub fn zero() -> Self {
+        Num {
+            value: Some(E::Fr::zero()),
+            lc: LinearCombination::zero()
+        }
+    }
+
+    pub fn get_value(&self) -> Option<E::Fr> {
+        self.value
+    }
+
+    /// Returns `coeff` times this linear combination.
+    pub fn lc(&self, coeff: E::Fr) -> LinearCombination<Var, E> {
+        let mut lc = LinearCombination::zero();
+
+        for &(var, ref c) in self.lc.as_ref() {
+            let mut tmp = *c;
+            tmp.mul_assign(&coeff);
+
+            lc = lc + (tmp, var);
+        }
+
+        lc
+    }
+
+    /// Adds `coeff` times the boolean `bit`.
+    pub fn add_bool_with_coeff(
+        self,
+        one: Var,
+        bit: &Boolean<Var>,
+        coeff: E::Fr
+    ) -> Self
+    {
+        let newval = match (self.value, bit.get_value()) {
+            (Some(mut curval), Some(bval)) => {
+                if bval {
+                    curval.add_assign(&coeff);
+                }
+
+                Some(curval)
+            },
+            _ => None
+        };
+
+        Num {
+            value: newval,
+            lc: self.lc + &bit.lc(one, coeff)
+        }
+    }
+
+    /// Adds `coeff` times the allocated number `num`.
+    pub fn add_num(
+        self,
+        num: &AllocatedNum<E, Var>,
+        coeff: E::Fr
+    ) -> Self
+    {
+        let newval = match (self.value, num.value) {
+            (Some(mut curval), Some(mut nval)) => {
+                nval.mul_assign(&coeff);
+                curval.add_assign(&nval);
+
+                Some(curval)
+            },
+            _ => None
+        };
+
+        Num {
+            value: newval,
+            lc: self.lc + (coeff, num.variable)
+        }
+    }
+
+    /// Adds `coeff` times the linear combination `other`.
+    pub fn add_lc(
+        self,
+        other: &Self,
+        coeff: E::Fr
+    ) -> Self
+    {
+        let newval = match (self.value, other.value) {
+            (Some(mut curval), Some(mut oval)) => {
+                oval.mul_assign(&coeff);
+                curval.add_assign(&oval);
+
+                Some(curval)
+            },
+            _ => None
+        };
+
+        Num {
+            value: newval,
+            lc: self.lc + &other.lc(coeff)
+        }
+    }
+
+    /// Allocates a number equal to this linear
+    /// combination, at the cost of one constraint.
+    pub fn into_allocated_num<CS>(
+        self,
+        mut cs: CS
+    ) -> Result<AllocatedNum<E, Var>, SynthesisError>
+        where CS: ConstraintSystem<E, Variable=Var>
+    {
+        let num = AllocatedNum::alloc(&mut cs, || {
+            Ok(*self.value.get()?)
+        })?;
+
+        // Constrain: lc * 1 = num
+        let one = cs.one();
+        cs.enforce(
+            || "packing constraint",
+            self.lc,
+            LinearCombination::zero() + one,
+            LinearCombination::zero() + num.variable
+        );
+
+        Ok(num)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{SeedableRng, Rand, Rng, XorShiftRng};
+    use bellman::{ConstraintSystem};
+    use pairing::bls12_381::{Bls12, Fr};
+    use pairing::{Field, PrimeField, PrimeFieldRepr, BitIterator};
+    use ::circuit::test::*;
+    use super::{AllocatedNum, Num, Boolean, into_bits_cost, pack_into_nums, bits_to_num_lc};
+    use super::super::boolean::AllocatedBit;
+
+    #[test]
+    fn test_allocated_num() {
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        AllocatedNum::alloc(&mut cs, || Ok(Fr::one())).unwrap();
+
+        assert!(cs.get("num") == Fr::one());
+    }
+
+    #[test]
+    fn test_allocated_input() {
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let a = AllocatedNum::alloc_input(cs.namespace(|| "a"), || Ok(Fr::from_str("7").unwrap())).unwrap();
+        let b = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(Fr::from_str("7").unwrap())).unwrap();
+        a.assert_equal(&mut cs, &b).unwrap();
+
+        assert!(cs.is_satisfied());
+        assert_eq!(cs.num_constraints(), 1);
+        assert!(a.value.unwrap() == Fr::from_str("7").unwrap());
+
+        // Only the input is public.
+        assert!(cs.verify(&[Fr::from_str("7").unwrap()]));
+        assert!(cs.get("a/input num") == Fr::from_str("7").unwrap());
+
+        cs.set("a/input num", Fr::from_str("8").unwrap());
+        assert!(!cs.verify(&[Fr::from_str("7").unwrap()]));
+        assert_eq!(cs.which_is_unsatisfied(), Some("equality constraint"));
+    }
+
+    #[test]
+    fn test_allocated_constant() {
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let n = AllocatedNum::alloc_constant(cs.namespace(|| "a"), Fr::from_str("5").unwrap()).unwrap();
+        let n2 = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(Fr::from_str("3").unwrap())).unwrap();
+        let n3 = n.mul(&mut cs, &n2).unwrap();
+
+        assert!(cs.is_satisfied());
+        assert!(cs.get("a/constant num") == Fr::from_str("5").unwrap());
+        assert!(n3.value.unwrap() == Fr::from_str("15").unwrap());
+        cs.set("a/constant num", Fr::from_str("6").unwrap());
+        assert_eq!(cs.which_is_unsatisfied().unwrap(), "a/constant constraint");
+    }
+
+    #[test]
+    fn test_num_squaring() {
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let mut n = AllocatedNum::alloc(&mut cs, || Ok(Fr::from_str("3").unwrap())).unwrap();
+        let n2 = n.square(cs.namespace(|| "honest")).unwrap();
+
+        assert!(cs.is_satisfied());
+        assert!(cs.get("honest/squared num") == Fr::from_str("9").unwrap());
+        assert!(n2.value.unwrap() == Fr::from_str("9").unwrap());
+
+        // Squaring a wrong cached value gives a bad witness.
+        n.override_value(Some(Fr::from_str("4").unwrap()));
+        let n3 = n.square(cs.namespace(|| "corrupted")).unwrap();
+
+        assert!(n3.value.unwrap() == Fr::from_str("16").unwrap());
+        assert_eq!(cs.which_is_unsatisfied(), Some("corrupted/squaring constraint"));
+    }
+
+    #[test]
+    fn test_num_cube() {
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let n = AllocatedNum::alloc(&mut cs, || Ok(Fr::from_str("3").unwrap())).unwrap();
+        let n3 = n.cube(&mut cs).unwrap();
+
+        assert!(cs.is_satisfied());
+        assert_eq!(cs.num_constraints(), 2);
+        assert!(cs.get("square/squared num") == Fr::from_str("9").unwrap());
+        assert!(cs.get("cube/product num") == Fr::from_str("27").unwrap());
+        assert!(n3.value.unwrap() == Fr::from_str("27").unwrap());
+        cs.set("cube/product num", Fr::from_str("28").unwrap());
+        assert_eq!(cs.which_is_unsatisfied(), Some("cube/multiplication constraint"));
+        cs.set("cube/product num", Fr::from_str("27").unwrap());
+        cs.set("square/squared num", Fr::from_str("10").unwrap());
+        assert_eq!(cs.which_is_unsatisfied(), Some("square/squaring constraint"));
+    }
+
+    #[test]
+    fn test_num_pow_constant() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..10 {
+            let x: Fr = rng.gen();
+
+            {
+                let mut cs = TestConstraintSystem::<Bls12>::new();
+
+                let n = AllocatedNum::alloc(&mut cs, || Ok(x)).unwrap();
+                let n5 = n.pow_constant(&mut cs, 5).unwrap();
+
+                let mut expected = x;
+                expected.square();
+                expected.square();
+                expected.mul_assign(&x);
+
+                assert!(cs.is_satisfied());
+                assert_eq!(cs.num_constraints(), 3);
+                assert_eq!(n5.value.unwrap(), expected);
+                cs.set("multiply 0/product num", rng.gen());
+                assert_eq!(cs.which_is_unsatisfied().unwrap(), "multiply 0/multiplication constraint");
+            }
+
+            {
+                let mut cs = TestConstraintSystem::<Bls12>::new();
+
+                let n = AllocatedNum::alloc(&mut cs, || Ok(x)).unwrap();
+                let n0 = n.pow_constant(&mut cs, 0).unwrap();
+
+                assert!(cs.is_satisfied());
+                assert_eq!(n0.value.unwrap(), Fr::one());
+            }
+
+            {
+                let mut cs = TestConstraintSystem::<Bls12>::new();
+
+                let n = AllocatedNum::alloc(&mut cs, || Ok(x)).unwrap();
+                let n1 = n.pow_constant(&mut cs, 1).unwrap();
+
+                assert_eq!(cs.num_constraints(), 0);
+                assert_eq!(n1.value.unwrap(), x);
+            }
+        }
+    }
+
+    #[test]
+    fn test_num_multiplication() {
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let n = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(Fr::from_str("12").unwrap())).unwrap();
+        let n2 = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(Fr::from_str("10").unwrap())).unwrap();
+        let n3 = n.mul(&mut cs, &n2).unwrap();
+
+        assert!(cs.is_satisfied());
+        assert!(cs.get("product num") == Fr::from_str("120").unwrap());
+        assert!(n3.value.unwrap() == Fr::from_str("120").unwrap());
+        cs.set("product num", Fr::from_str("121").unwrap());
+        assert!(!cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_num_mul_add() {
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let a = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(Fr::from_str("2").unwrap())).unwrap();
+        let b = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(Fr::from_str("3").unwrap())).unwrap();
+        let c = AllocatedNum::alloc(cs.namespace(|| "c"), || Ok(Fr::from_str("4").unwrap())).unwrap();
+        let r = a.mul_add(&mut cs, &b, &c).unwrap();
+
+        assert!(cs.is_satisfied());
+        assert_eq!(cs.num_constraints(), 1);
+        assert!(cs.get("mul add num") == Fr::from_str("10").unwrap());
+        assert!(r.value.unwrap() == Fr::from_str("10").unwrap());
+        cs.set("mul add num", Fr::from_str("6").unwrap());
+        assert_eq!(cs.which_is_unsatisfied(), Some("mul add constraint"));
+    }
+
+    #[test]
+    fn test_num_add_checked() {
+        let two_62 = Fr::from_str("4611686018427387904").unwrap();
+        let two_63 = Fr::from_str("9223372036854775808").unwrap();
+
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let a = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(two_62)).unwrap();
+            let b = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(two_62)).unwrap();
+            let c = a.add_checked(cs.namespace(|| "sum"), &b, 64).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(cs.num_constraints(), 1 + 64 + 1);
+            assert!(c.get_value().unwrap() == two_63);
+        }
+
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let a = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(two_63)).unwrap();
+            let b = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(two_63)).unwrap();
+            a.add_checked(cs.namespace(|| "sum"), &b, 64).unwrap();
+
+            assert_eq!(cs.which_is_unsatisfied(), Some("sum/range check/unpacking constraint"));
+        }
+    }
+
+    #[test]
+    fn test_num_assert_multiple_of() {
+        let three = Fr::from_str("3").unwrap();
+
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let n = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(Fr::from_str("12").unwrap())).unwrap();
+            let q = n.assert_multiple_of(cs.namespace(|| "multiple"), three, 8).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(cs.num_constraints(), 1 + 8 + 1);
+            assert!(q.get_value().unwrap() == Fr::from_str("4").unwrap());
+
+            cs.set("multiple/quotient/num", Fr::from_str("5").unwrap());
+            assert_eq!(cs.which_is_unsatisfied(), Some("multiple/multiple constraint"));
+        }
+
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            // 13 / 3 in the field is far too large.
+            let n = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(Fr::from_str("13").unwrap())).unwrap();
+            n.assert_multiple_of(cs.namespace(|| "multiple"), three, 8).unwrap();
+
+            assert_eq!(cs.which_is_unsatisfied(), Some("multiple/range check/unpacking constraint"));
+        }
+
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let n = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(Fr::from_str("12").unwrap())).unwrap();
+            assert!(n.assert_multiple_of(cs.namespace(|| "multiple"), Fr::zero(), 8).is_err());
+        }
+    }
+
+    #[test]
+    fn test_into_bits_bounded() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..10 {
+            let value: u64 = rng.gen();
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let n = AllocatedNum::alloc(&mut cs, || Ok(Fr::from_repr(value.into()).unwrap())).unwrap();
+            let bits = n.into_bits_bounded(cs.namespace(|| "bits"), 64).unwrap();
+
+            // Only 64 bits are allocated, with no field
+            // check on top of the packing constraint.
+            assert!(cs.is_satisfied());
+            assert_eq!(cs.num_constraints(), 64 + 1);
+            assert_eq!(bits.len(), 64);
+
+            for (i, b) in bits.iter().enumerate() {
+                assert_eq!(b.get_value().unwrap(), (value >> (63 - i)) & 1 == 1);
+            }
+        }
+
+        // A value that does not fit is unsatisfiable.
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let n = AllocatedNum::alloc(&mut cs, || Ok(Fr::from_str("18446744073709551616").unwrap())).unwrap();
+        n.into_bits_bounded(cs.namespace(|| "bits"), 64).unwrap();
+
+        assert_eq!(cs.which_is_unsatisfied(), Some("bits/unpacking constraint"));
+    }
+
+    #[test]
+    fn test_num_addition() {
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let n = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(Fr::from_str("12").unwrap())).unwrap();
+        let n2 = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(Fr::from_str("10").unwrap())).unwrap();
+        let n3 = n.add(&mut cs, &n2).unwrap();
+
+        assert!(cs.is_satisfied());
+        assert!(cs.get("sum num") == Fr::from_str("22").unwrap());
+        assert!(n3.value.unwrap() == Fr::from_str("22").unwrap());
+        cs.set("sum num", Fr::from_str("23").unwrap());
+        assert!(!cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_num_add_constant() {
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let n = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(Fr::from_str("12").unwrap())).unwrap();
+        let n2 = n.add_constant(&mut cs, Fr::from_str("30").unwrap()).unwrap();
+
+        assert!(cs.is_satisfied());
+        assert_eq!(cs.num_constraints(), 1);
+        assert!(cs.get("sum num") == Fr::from_str("42").unwrap());
+        assert!(n2.value.unwrap() == Fr::from_str("42").unwrap());
+        cs.set("sum num", Fr::from_str("43").unwrap());
+        assert!(!cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_num_mul_by_constant() {
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let n = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(Fr::from_str("6").unwrap())).unwrap();
+        let n2 = n.mul_by_constant(&mut cs, Fr::from_str("7").unwrap()).unwrap();
+
+        assert!(cs.is_satisfied());
+        assert_eq!(cs.num_constraints(), 1);
+        assert!(cs.get("scaled num") == Fr::from_str("42").unwrap());
+        assert!(n2.value.unwrap() == Fr::from_str("42").unwrap());
+        cs.set("scaled num", Fr::from_str("43").unwrap());
+        assert!(!cs.is_satisfied());
+        cs.set("scaled num", Fr::from_str("42").unwrap());
+
+        // The scaled term can be folded into other constraints.
+        cs.enforce(
+            || "folded",
+            n.lc(Fr::from_str("7").unwrap()),
+            n.lc(Fr::one()),
+            n2.lc(Fr::from_str("6").unwrap())
+        );
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_num_negation() {
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let n = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(Fr::one())).unwrap();
+        let n2 = n.negate(&mut cs).unwrap();
+
+        let mut negone = Fr::one();
+        negone.negate();
+
+        assert!(cs.is_satisfied());
+        assert!(cs.get("negated num") == negone);
+        assert!(n2.value.unwrap() == negone);
+        cs.set("negated num", Fr::one());
+        assert_eq!(cs.which_is_unsatisfied().unwrap(), "negation constraint");
+    }
+
+    #[test]
+    fn test_num_sum() {
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let nums = (1..5).map(|i| {
+                AllocatedNum::alloc(
+                    cs.namespace(|| format!("num {}", i)),
+                    || Ok(Fr::from_str(&format!("{}", i)).unwrap())
+                ).unwrap()
+            }).collect::<Vec<_>>();
+
+            let sum = AllocatedNum::sum(&mut cs, &nums).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(cs.num_constraints(), 1);
+            assert!(cs.get("sum num") == Fr::from_str("10").unwrap());
+            assert!(sum.value.unwrap() == Fr::from_str("10").unwrap());
+            cs.set("sum num", Fr::from_str("11").unwrap());
+            assert_eq!(cs.which_is_unsatisfied().unwrap(), "summation constraint");
+        }
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let sum = AllocatedNum::sum(&mut cs, &[]).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert!(sum.value.unwrap().is_zero());
+            cs.set("constant num", Fr::one());
+            assert!(!cs.is_satisfied());
+        }
+    }
+
+    #[test]
+    fn test_num_subtraction() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..100 {
+            let a: Fr = rng.gen();
+            let b: Fr = rng.gen();
+            let mut expected = a;
+            expected.sub_assign(&b);
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let n = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(a)).unwrap();
+            let n2 = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(b)).unwrap();
+            let n3 = n.sub(&mut cs, &n2).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert!(cs.get("difference num") == expected);
+            assert!(n3.value.unwrap() == expected);
+            cs.set("difference num", rng.gen());
+            assert_eq!(cs.which_is_unsatisfied().unwrap(), "subtraction constraint");
+        }
+    }
+
+    #[test]
+    fn test_num_inverse() {
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let n = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(Fr::from_str("7").unwrap())).unwrap();
+            let inv = n.inverse(cs.namespace(|| "inverse")).unwrap();
+            let product = n.mul(cs.namespace(|| "product"), &inv).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert!(product.value.unwrap() == Fr::one());
+            cs.set("inverse/inverse num", Fr::from_str("7").unwrap());
+            assert_eq!(cs.which_is_unsatisfied().unwrap(), "inverse/inversion constraint");
+        }
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let n = AllocatedNum::alloc(&mut cs, || Ok(Fr::zero())).unwrap();
+            assert!(n.inverse(&mut cs).is_err());
+        }
+    }
+
+    #[test]
+    fn test_num_division() {
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let n = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(Fr::from_str("120").unwrap())).unwrap();
+            let n2 = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(Fr::from_str("10").unwrap())).unwrap();
+            let n3 = n.div(&mut cs, &n2).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(cs.num_constraints(), 1);
+            assert!(cs.get("quotient num") == Fr::from_str("12").unwrap());
+            assert!(n3.value.unwrap() == Fr::from_str("12").unwrap());
+            cs.set("quotient num", Fr::from_str("13").unwrap());
+            assert!(!cs.is_satisfied());
+        }
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let n = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(Fr::from_str("120").unwrap())).unwrap();
+            let n2 = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(Fr::zero())).unwrap();
+            assert!(n.div(&mut cs, &n2).is_err());
+        }
+    }
+
+    #[test]
+    fn test_num_inner_product() {
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let alloc = |cs: &mut TestConstraintSystem<Bls12>, name: &str, v: &str| {
+            AllocatedNum::alloc(cs.namespace(|| name), || Ok(Fr::from_str(v).unwrap())).unwrap()
+        };
+
+        let a = vec![alloc(&mut cs, "a0", "2"), alloc(&mut cs, "a1", "3")];
+        let b = vec![alloc(&mut cs, "b0", "4"), alloc(&mut cs, "b1", "5")];
+
+        let c = AllocatedNum::inner_product(&mut cs, &a, &b).unwrap();
+
+        assert!(cs.is_satisfied());
+        assert_eq!(cs.num_constraints(), 3);
+        assert!(c.value.unwrap() == Fr::from_str("23").unwrap());
+        cs.set("product 1/product num", Fr::from_str("16").unwrap());
+        assert_eq!(cs.which_is_unsatisfied().unwrap(), "product 1/multiplication constraint");
+    }
+
+    #[test]
+    fn test_num_conditional_reversal() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let a = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(rng.gen())).unwrap();
+            let b = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(rng.gen())).unwrap();
+            let condition = Boolean::constant(false);
+            let (c, d) = AllocatedNum::conditionally_reverse(&mut cs, &a, &b, &condition).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(cs.num_constraints(), 0);
+
+            assert_eq!(a.value.unwrap(), c.value.unwrap());
+            assert_eq!(b.value.unwrap(), d.value.unwrap());
+        }
+
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let a = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(rng.gen())).unwrap();
+            let b = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(rng.gen())).unwrap();
+            let condition = Boolean::constant(true);
+            let (c, d) = AllocatedNum::conditionally_reverse(&mut cs, &a, &b, &condition).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(cs.num_constraints(), 0);
+
+            assert_eq!(a.value.unwrap(), d.value.unwrap());
+            assert_eq!(b.value.unwrap(), c.value.unwrap());
+        }
+    }
+
+    #[test]
+    fn test_num_conditional_select_multiple() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for &condition in &[true, false] {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let a: Vec<_> = (0..3).map(|i| {
+                AllocatedNum::alloc(cs.namespace(|| format!("a {}", i)), || Ok(rng.gen())).unwrap()
+            }).collect();
+            let b: Vec<_> = (0..3).map(|i| {
+                AllocatedNum::alloc(cs.namespace(|| format!("b {}", i)), || Ok(rng.gen())).unwrap()
+            }).collect();
+            let cond = Boolean::from(
+                AllocatedBit::alloc(cs.namespace(|| "condition"), Some(condition)).unwrap()
+            );
+
+            let c = AllocatedNum::conditionally_select_multiple(cs.namespace(|| "select"), &a, &b, &cond).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(cs.num_constraints(), 1 + 3);
+
+            let expected = if condition { &a } else { &b };
+            let other = if condition { &b } else { &a };
+            for (c, e) in c.iter().zip(expected.iter()) {
+                assert_eq!(c.value.unwrap(), e.value.unwrap());
+            }
+
+            cs.set("select/element 2/conditional select result/num", other[2].value.unwrap());
+            assert_eq!(cs.which_is_unsatisfied().unwrap(), "select/element 2/conditional select");
+        }
+    }
+
+    #[test]
+    fn test_num_conditional_select() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let a = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(rng.gen())).unwrap();
+            let b = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(rng.gen())).unwrap();
+            let condition = Boolean::from(
+                AllocatedBit::alloc(cs.namespace(|| "condition"), Some(true)).unwrap()
+            );
+            let c = AllocatedNum::conditionally_select(&mut cs, &a, &b, &condition).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(a.value.unwrap(), c.value.unwrap());
+
+            cs.set("conditional select result/num", b.value.unwrap());
+            assert_eq!(cs.which_is_unsatisfied().unwrap(), "conditional select");
+        }
+
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let a = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(rng.gen())).unwrap();
+            let b = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(rng.gen())).unwrap();
+            let condition = Boolean::from(
+                AllocatedBit::alloc(cs.namespace(|| "condition"), Some(false)).unwrap()
+            );
+            let c = AllocatedNum::conditionally_select(&mut cs, &a, &b, &condition).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(b.value.unwrap(), c.value.unwrap());
 
-        // (1-c)(x) + (c)(-x) = r
-        // x - 2cx = r
-        // (2x) * (c) = x - r
+            cs.set("conditional select result/num", a.value.unwrap());
+            assert_eq!(cs.which_is_unsatisfied().unwrap(), "conditional select");
+        }
 
-        let one = cs.one();
-        cs.enforce(
-            || "conditional negation",
-            LinearCombination::zero() + self.variable + self.variable,
-            condition.lc(one, E::Fr::one()),
-            LinearCombination::zero() + self.variable - r.variable
-        );
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
 
-        Ok(r)
-    }
+            let a = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(rng.gen())).unwrap();
+            let b = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(rng.gen())).unwrap();
 
-    pub fn get_value(&self) -> Option<E::Fr> {
-        self.value
-    }
+            let c = AllocatedNum::conditionally_select(&mut cs, &a, &b, &Boolean::constant(true)).unwrap();
+            let d = AllocatedNum::conditionally_select(&mut cs, &a, &b, &Boolean::constant(false)).unwrap();
 
-    pub fn get_variable(&self) -> Var {
-        self.variable
+            assert!(cs.is_satisfied());
+            assert_eq!(cs.num_constraints(), 0);
+            assert_eq!(a.value.unwrap(), c.value.unwrap());
+            assert_eq!(b.value.unwrap(), d.value.unwrap());
+        }
     }
-}
-
-#[cfg(test)]
-mod test {
-    use rand::{SeedableRng, Rand, Rng, XorShiftRng};
-    use bellman::{ConstraintSystem};
-    use pairing::bls12_381::{Bls12, Fr};
-    use pairing::{Field, PrimeField, BitIterator};
-    use ::circuit::test::*;
-    use super::{AllocatedNum, Boolean};
-    use super::super::boolean::AllocatedBit;
 
     #[test]
-    fn test_allocated_num() {
-        let mut cs = TestConstraintSystem::<Bls12>::new();
+    fn test_select_from_table() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
 
-        AllocatedNum::alloc(&mut cs, || Ok(Fr::one())).unwrap();
+        let table: Vec<Fr> = (0..4).map(|_| rng.gen()).collect();
 
-        assert!(cs.get("num") == Fr::one());
-    }
+        for index in 0..4 {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
 
-    #[test]
-    fn test_num_squaring() {
-        let mut cs = TestConstraintSystem::<Bls12>::new();
+            let bits = (0..2).map(|i| {
+                Boolean::from(
+                    AllocatedBit::alloc(cs.namespace(|| format!("bit {}", i)), Some((index >> i) & 1 == 1)).unwrap()
+                )
+            }).collect::<Vec<_>>();
 
-        let n = AllocatedNum::alloc(&mut cs, || Ok(Fr::from_str("3").unwrap())).unwrap();
-        let n2 = n.square(&mut cs).unwrap();
+            let r = AllocatedNum::select_from_table(cs.namespace(|| "select"), &bits, &table).unwrap();
 
-        assert!(cs.is_satisfied());
-        assert!(cs.get("squared num") == Fr::from_str("9").unwrap());
-        assert!(n2.value.unwrap() == Fr::from_str("9").unwrap());
-        cs.set("squared num", Fr::from_str("10").unwrap());
-        assert!(!cs.is_satisfied());
-    }
+            assert!(cs.is_satisfied());
+            assert_eq!(cs.num_constraints(), 2 + 3);
+            assert_eq!(r.value.unwrap(), table[index]);
 
-    #[test]
-    fn test_num_multiplication() {
-        let mut cs = TestConstraintSystem::<Bls12>::new();
+            cs.set("select/level 0, entry 1/num", rng.gen());
+            assert_eq!(cs.which_is_unsatisfied().unwrap(), "select/level 0, entry 1 selection");
+        }
 
-        let n = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(Fr::from_str("12").unwrap())).unwrap();
-        let n2 = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(Fr::from_str("10").unwrap())).unwrap();
-        let n3 = n.mul(&mut cs, &n2).unwrap();
+        {
+            // A single entry needs no index bits.
+            let mut cs = TestConstraintSystem::<Bls12>::new();
 
-        assert!(cs.is_satisfied());
-        assert!(cs.get("product num") == Fr::from_str("120").unwrap());
-        assert!(n3.value.unwrap() == Fr::from_str("120").unwrap());
-        cs.set("product num", Fr::from_str("121").unwrap());
-        assert!(!cs.is_satisfied());
+            let r = AllocatedNum::select_from_table(cs.namespace(|| "select"), &[], &table[0..1]).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(r.value.unwrap(), table[0]);
+        }
     }
 
     #[test]
-    fn test_num_conditional_reversal() {
+    fn test_num_conditional_addition() {
         let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
-        {
+
+        for &condition_value in &[false, true] {
             let mut cs = TestConstraintSystem::<Bls12>::new();
 
             let a = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(rng.gen())).unwrap();
             let b = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(rng.gen())).unwrap();
-            let condition = Boolean::constant(false);
-            let (c, d) = AllocatedNum::conditionally_reverse(&mut cs, &a, &b, &condition).unwrap();
+            let condition = Boolean::from(
+                AllocatedBit::alloc(cs.namespace(|| "condition"), Some(condition_value)).unwrap()
+            );
+            let c = a.conditionally_add(&mut cs, &b, &condition).unwrap();
 
             assert!(cs.is_satisfied());
+            assert_eq!(cs.num_constraints(), 2);
 
-            assert_eq!(a.value.unwrap(), c.value.unwrap());
-            assert_eq!(b.value.unwrap(), d.value.unwrap());
+            let mut expected = a.value.unwrap();
+            if condition_value {
+                expected.add_assign(&b.value.unwrap());
+            }
+            assert_eq!(c.value.unwrap(), expected);
+
+            cs.set("conditional addition result/num", rng.gen());
+            assert_eq!(cs.which_is_unsatisfied().unwrap(), "conditional addition");
         }
 
         {
@@ -455,13 +2436,17 @@ mod test {
 
             let a = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(rng.gen())).unwrap();
             let b = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(rng.gen())).unwrap();
-            let condition = Boolean::constant(true);
-            let (c, d) = AllocatedNum::conditionally_reverse(&mut cs, &a, &b, &condition).unwrap();
+
+            let c = a.conditionally_add(cs.namespace(|| "true"), &b, &Boolean::constant(true)).unwrap();
+            let d = a.conditionally_add(cs.namespace(|| "false"), &b, &Boolean::constant(false)).unwrap();
 
             assert!(cs.is_satisfied());
+            assert_eq!(cs.num_constraints(), 1);
 
-            assert_eq!(a.value.unwrap(), d.value.unwrap());
-            assert_eq!(b.value.unwrap(), c.value.unwrap());
+            let mut expected = a.value.unwrap();
+            expected.add_assign(&b.value.unwrap());
+            assert_eq!(c.value.unwrap(), expected);
+            assert_eq!(d.value.unwrap(), a.value.unwrap());
         }
     }
 
@@ -490,11 +2475,10 @@ mod test {
             let b = Boolean::constant(false);
             let n2 = n.conditionally_negate(&mut cs, &b).unwrap();
 
+            // A constant false condition is free.
             assert!(cs.is_satisfied());
-            assert!(cs.get("conditional negation result/num") == Fr::one());
+            assert_eq!(cs.num_constraints(), 0);
             assert!(n2.value.unwrap() == Fr::one());
-            cs.set("conditional negation result/num", Fr::from_str("2").unwrap());
-            assert!(!cs.is_satisfied());
         }
 
         {
@@ -566,53 +2550,349 @@ mod test {
         }
     }
 
-    #[test]
-    fn test_num_nonzero() {
-        {
-            let mut cs = TestConstraintSystem::<Bls12>::new();
+    #[test]
+    fn test_num_conditional_negation_bit() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for &condition in &[false, true] {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let x: Fr = rng.gen();
+            let mut negx = x;
+            negx.negate();
+
+            let n = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(x)).unwrap();
+            let bit = AllocatedBit::alloc(cs.namespace(|| "condition"), Some(condition)).unwrap();
+
+            let n2 = n.conditionally_negate_bit(cs.namespace(|| "is"), &bit).unwrap();
+            // The negated view of the same bit must select
+            // the other branch.
+            let n3 = n.conditionally_negate(cs.namespace(|| "not"), &Boolean::from(bit).not()).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(n2.value.unwrap(), if condition { negx } else { x });
+            assert_eq!(n3.value.unwrap(), if condition { x } else { negx });
+
+            cs.set("not/conditional negation result/num", n2.value.unwrap());
+            assert_eq!(cs.which_is_unsatisfied().unwrap(), "not/conditional negation");
+        }
+    }
+
+    #[test]
+    fn test_num_batch_invert() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let nums = (0..5).map(|i| {
+                AllocatedNum::alloc(cs.namespace(|| format!("num {}", i)), || Ok(rng.gen())).unwrap()
+            }).collect::<Vec<_>>();
+
+            let inverses = AllocatedNum::batch_invert(cs.namespace(|| "batch inversion"), &nums).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(cs.num_constraints(), 3 * 5 - 2);
+            assert_eq!(inverses.len(), 5);
+
+            for (num, inv) in nums.iter().zip(inverses.iter()) {
+                let mut tmp = num.value.unwrap();
+                tmp.mul_assign(&inv.value.unwrap());
+                assert_eq!(tmp, Fr::one());
+            }
+
+            cs.set("batch inversion/inverse 2/product num", rng.gen());
+            assert_eq!(cs.which_is_unsatisfied().unwrap(), "batch inversion/inverse 2/multiplication constraint");
+        }
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let nums = (0..5).map(|i| {
+                AllocatedNum::alloc(cs.namespace(|| format!("num {}", i)), || {
+                    if i == 3 { Ok(Fr::zero()) } else { Ok(rng.gen()) }
+                }).unwrap()
+            }).collect::<Vec<_>>();
+
+            assert!(AllocatedNum::batch_invert(&mut cs, &nums).is_err());
+        }
+    }
+
+    #[test]
+    fn test_num_nonzero() {
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let n = AllocatedNum::alloc(&mut cs, || Ok(Fr::from_str("3").unwrap())).unwrap();
+            n.assert_nonzero(&mut cs).unwrap();
+
+            assert!(cs.is_satisfied());
+            cs.set("ephemeral inverse", Fr::from_str("3").unwrap());
+            assert!(cs.which_is_unsatisfied() == Some("nonzero assertion constraint"));
+        }
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let n = AllocatedNum::alloc(&mut cs, || Ok(Fr::zero())).unwrap();
+            assert!(n.assert_nonzero(&mut cs).is_err());
+        }
+    }
+
+    #[test]
+    fn test_num_nonzero_with_inverse() {
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let n = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(Fr::from_str("3").unwrap())).unwrap();
+            let inv = n.assert_nonzero_with_inverse(cs.namespace(|| "nonzero")).unwrap();
+            let product = n.mul(cs.namespace(|| "product"), &inv).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(cs.num_constraints(), 2);
+            assert!(product.value.unwrap() == Fr::one());
+            cs.set("nonzero/ephemeral inverse", Fr::from_str("3").unwrap());
+            assert_eq!(cs.which_is_unsatisfied(), Some("nonzero/nonzero assertion constraint"));
+        }
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let n = AllocatedNum::alloc(&mut cs, || Ok(Fr::zero())).unwrap();
+            assert!(n.assert_nonzero_with_inverse(&mut cs).is_err());
+        }
+    }
+
+    #[test]
+    fn test_num_equals_constant() {
+        let seven = Fr::from_str("7").unwrap();
+        let eight = Fr::from_str("8").unwrap();
+
+        for &(c, expected) in &[(seven, true), (eight, false)] {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let n = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(seven)).unwrap();
+            let eq = n.equals_constant(cs.namespace(|| "equals"), c).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(cs.num_constraints(), 3);
+            assert_eq!(eq.get_value().unwrap(), expected);
+
+            // The result cannot be flipped.
+            let flipped = if expected { Fr::zero() } else { Fr::one() };
+            cs.set("equals/is equal/boolean", flipped);
+            assert!(!cs.is_satisfied());
+        }
+    }
+
+    #[test]
+    fn test_num_equality() {
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let a = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(Fr::from_str("3").unwrap())).unwrap();
+            let b = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(Fr::from_str("3").unwrap())).unwrap();
+            a.assert_equal(&mut cs, &b).unwrap();
+
+            assert!(cs.is_satisfied());
+            cs.set("b/num", Fr::from_str("4").unwrap());
+            assert_eq!(cs.which_is_unsatisfied().unwrap(), "equality constraint");
+        }
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let a = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(Fr::from_str("3").unwrap())).unwrap();
+            let b = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(Fr::from_str("4").unwrap())).unwrap();
+            a.assert_equal(&mut cs, &b).unwrap();
+
+            assert!(!cs.is_satisfied());
+        }
+    }
+
+    #[test]
+    fn test_num_inequality() {
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let a = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(Fr::from_str("3").unwrap())).unwrap();
+            let b = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(Fr::from_str("4").unwrap())).unwrap();
+            a.enforce_not_equal(&mut cs, &b).unwrap();
+
+            assert!(cs.is_satisfied());
+            cs.set("b/num", Fr::from_str("3").unwrap());
+            assert_eq!(cs.which_is_unsatisfied().unwrap(), "inequality constraint");
+        }
+        {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let a = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(Fr::from_str("3").unwrap())).unwrap();
+            let b = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(Fr::from_str("3").unwrap())).unwrap();
+            assert!(a.enforce_not_equal(&mut cs, &b).is_err());
+        }
+    }
+
+    #[test]
+    fn test_into_bits_strict() {
+        let mut negone = Fr::one();
+        negone.negate();
+
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let n = AllocatedNum::alloc(&mut cs, || Ok(negone)).unwrap();
+        n.into_bits_strict(&mut cs).unwrap();
+
+        assert!(cs.is_satisfied());
+
+        // make the bit representation the characteristic
+        cs.set("bit 254/boolean", Fr::one());
+
+        // this makes the unpacking constraint fail
+        assert_eq!(cs.which_is_unsatisfied().unwrap(), "unpacking constraint");
+
+        // fix it by making the number zero (congruent to the characteristic)
+        cs.set("num", Fr::zero());
+
+        // and constraint is disturbed during enforce in field check
+        assert_eq!(cs.which_is_unsatisfied().unwrap(), "nand 121/AND 0/and constraint");
+        cs.set("nand 121/AND 0/and result", Fr::one());
+
+        // now the nand should fail (enforce in field is working)
+        assert_eq!(cs.which_is_unsatisfied().unwrap(), "nand 121/enforce nand");
+    }
+
+    #[test]
+    fn test_from_variable() {
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let var = cs.alloc(|| "raw", || Ok(Fr::from_str("12").unwrap())).unwrap();
+        let a = AllocatedNum::from_variable(var, Some(Fr::from_str("12").unwrap()));
+        let b = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(Fr::from_str("10").unwrap())).unwrap();
+
+        let c = a.mul(cs.namespace(|| "mul"), &b).unwrap();
+
+        assert!(cs.is_satisfied());
+        assert_eq!(cs.num_constraints(), 1);
+        assert!(c.get_value().unwrap() == Fr::from_str("120").unwrap());
+
+        cs.set("raw", Fr::from_str("11").unwrap());
+        assert_eq!(cs.which_is_unsatisfied(), Some("mul/multiplication constraint"));
+    }
+
+    #[test]
+    fn test_num_from_bit() {
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let a = AllocatedBit::alloc(cs.namespace(|| "a"), Some(true)).unwrap();
+        let b = AllocatedBit::alloc(cs.namespace(|| "b"), Some(false)).unwrap();
+        let c = AllocatedBit::xor(cs.namespace(|| "a xor b"), &a, &b).unwrap();
+
+        let num = AllocatedNum::from_bit(&c);
+        assert!(num.get_value().unwrap() == Fr::one());
+        assert_eq!(cs.num_constraints(), 3);
+
+        let x = AllocatedNum::alloc(cs.namespace(|| "x"), || Ok(Fr::from_str("41").unwrap())).unwrap();
+        let sum = num.add(cs.namespace(|| "sum"), &x).unwrap();
+
+        assert!(cs.is_satisfied());
+        assert!(sum.get_value().unwrap() == Fr::from_str("42").unwrap());
+
+        // The num is the bit itself.
+        cs.set("a xor b/xor result", Fr::zero());
+        assert_eq!(cs.which_is_unsatisfied(), Some("a xor b/xor constraint"));
+
+        assert!(AllocatedNum::<Bls12, _>::from_bit(&b).get_value().unwrap() == Fr::zero());
+    }
+
+    #[test]
+    fn test_num_from_u64() {
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let a = AllocatedNum::from_u64(cs.namespace(|| "a"), 1000).unwrap();
+        let b = AllocatedNum::from_u64(cs.namespace(|| "b"), u64::max_value()).unwrap();
+
+        assert!(cs.is_satisfied());
+        assert_eq!(cs.num_constraints(), 2);
+        assert!(a.get_value().unwrap() == Fr::from_str("1000").unwrap());
+        assert!(b.get_value().unwrap() == Fr::from_str("18446744073709551615").unwrap());
+
+        cs.set("a/constant num", Fr::from_str("1001").unwrap());
+        assert_eq!(cs.which_is_unsatisfied(), Some("a/constant constraint"));
+    }
+
+    #[test]
+    fn test_num_identities() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let value = Fr::rand(&mut rng);
+        let x = AllocatedNum::alloc(cs.namespace(|| "x"), || Ok(value)).unwrap();
+
+        let one = AllocatedNum::one(cs.namespace(|| "one")).unwrap();
+        let zero = AllocatedNum::zero(cs.namespace(|| "zero")).unwrap();
+        assert_eq!(cs.num_constraints(), 2);
+
+        let product = one.mul(cs.namespace(|| "product"), &x).unwrap();
+        let sum = zero.add(cs.namespace(|| "sum"), &x).unwrap();
 
-            let n = AllocatedNum::alloc(&mut cs, || Ok(Fr::from_str("3").unwrap())).unwrap();
-            n.assert_nonzero(&mut cs).unwrap();
+        assert!(cs.is_satisfied());
+        assert!(product.get_value().unwrap() == value);
+        assert!(sum.get_value().unwrap() == value);
 
-            assert!(cs.is_satisfied());
-            cs.set("ephemeral inverse", Fr::from_str("3").unwrap());
-            assert!(cs.which_is_unsatisfied() == Some("nonzero assertion constraint"));
-        }
-        {
-            let mut cs = TestConstraintSystem::<Bls12>::new();
+        product.assert_equal(cs.namespace(|| "product equality"), &x).unwrap();
+        sum.assert_equal(cs.namespace(|| "sum equality"), &x).unwrap();
+        assert!(cs.is_satisfied());
 
-            let n = AllocatedNum::alloc(&mut cs, || Ok(Fr::zero())).unwrap();
-            assert!(n.assert_nonzero(&mut cs).is_err());
-        }
+        // Both constants are constrained.
+        cs.set("zero/constant num", Fr::one());
+        assert_eq!(cs.which_is_unsatisfied(), Some("zero/constant constraint"));
+        cs.set("zero/constant num", Fr::zero());
+        assert!(cs.is_satisfied());
+
+        cs.set("one/constant num", Fr::zero());
+        assert!(!cs.is_satisfied());
+        assert_eq!(cs.which_is_unsatisfied(), Some("one/constant constraint"));
     }
 
     #[test]
-    fn test_into_bits_strict() {
-        let mut negone = Fr::one();
-        negone.negate();
-
+    fn test_into_bits_cost() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
         let mut cs = TestConstraintSystem::<Bls12>::new();
 
-        let n = AllocatedNum::alloc(&mut cs, || Ok(negone)).unwrap();
-        n.into_bits_strict(&mut cs).unwrap();
+        let n = AllocatedNum::alloc(&mut cs, || Ok(Fr::rand(&mut rng))).unwrap();
+        n.into_bits(&mut cs).unwrap();
 
         assert!(cs.is_satisfied());
+        assert_eq!(cs.num_constraints(), into_bits_cost::<Bls12>());
+    }
 
-        // make the bit representation the characteristic
-        cs.set("bit 254/boolean", Fr::one());
+    #[test]
+    fn test_into_bits_non_canonical() {
+        let five = Fr::from_str("5").unwrap();
 
-        // this makes the unpacking constraint fail
-        assert_eq!(cs.which_is_unsatisfied().unwrap(), "unpacking constraint");
+        // 5 + modulus, in big-endian order
+        let mut repr = Fr::char();
+        repr.add_nocarry(&5.into());
+        let alias = BitIterator::new(repr).skip(1).collect::<Vec<_>>();
 
-        // fix it by making the number zero (congruent to the characteristic)
-        cs.set("num", Fr::zero());
+        for &strict in &[false, true] {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
 
-        // and constraint is disturbed during enforce in field check
-        assert_eq!(cs.which_is_unsatisfied().unwrap(), "nand 121/AND 0/and constraint");
-        cs.set("nand 121/AND 0/and result", Fr::one());
+            let n = AllocatedNum::alloc(&mut cs, || Ok(five)).unwrap();
 
-        // now the nand should fail (enforce in field is working)
-        assert_eq!(cs.which_is_unsatisfied().unwrap(), "nand 121/enforce nand");
+            if strict {
+                n.into_bits_strict(&mut cs).unwrap();
+            } else {
+                n.into_bits(&mut cs).unwrap();
+            }
+
+            assert!(cs.is_satisfied());
+
+            for (i, b) in alias.iter().enumerate() {
+                cs.set(&format!("bit {}/boolean", i), if *b { Fr::one() } else { Fr::zero() });
+            }
+
+            // Both representations satisfy the packing
+            // constraint, but only `into_bits_strict`
+            // rejects the non-canonical one.
+            assert_eq!(cs.is_satisfied(), !strict);
+        }
     }
 
     #[test]
@@ -655,6 +2935,211 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_into_bits_le() {
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let n = AllocatedNum::alloc(&mut cs, || Ok(Fr::from_str("11").unwrap())).unwrap();
+        let bits = n.into_bits_le(&mut cs).unwrap();
+
+        assert!(cs.is_satisfied());
+        assert_eq!(bits.len(), Fr::NUM_BITS as usize);
+
+        let expected = [true, true, false, true];
+        for (i, b) in bits.iter().enumerate() {
+            assert_eq!(b.get_value().unwrap(), i < 4 && expected[i]);
+        }
+
+        assert_eq!(cs.get("bit 254/boolean"), Fr::one());
+    }
+
+    #[test]
+    fn test_assert_fits_in_bits() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..100 {
+            let v: u64 = rng.gen();
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let n = AllocatedNum::alloc(&mut cs, || Ok(Fr::from_repr(v.into()).unwrap())).unwrap();
+            let bits = n.assert_fits_in_bits(&mut cs, 64).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(bits.len(), 64);
+            assert_eq!(cs.num_constraints(), 65);
+
+            for (i, b) in bits.iter().enumerate() {
+                assert_eq!(b.get_value().unwrap(), (v >> i) & 1 == 1);
+            }
+        }
+
+        {
+            // 2^64
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let n = AllocatedNum::alloc(&mut cs, || Ok(Fr::from_str("18446744073709551616").unwrap())).unwrap();
+            n.assert_fits_in_bits(&mut cs, 64).unwrap();
+
+            assert_eq!(cs.which_is_unsatisfied().unwrap(), "unpacking constraint");
+        }
+    }
+
+    #[test]
+    fn test_less_than() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for &num_bits in &[1, 8, 32, 64] {
+            let max = if num_bits == 64 { !0u64 } else { (1u64 << num_bits) - 1 };
+
+            let mut cases = vec![(0, 0), (0, max), (max, 0), (max, max)];
+            for _ in 0..20 {
+                let x = rng.gen::<u64>() & max;
+                let y = rng.gen::<u64>() & max;
+                cases.push((x, y));
+                cases.push((x, x));
+            }
+
+            for (x, y) in cases {
+                let mut cs = TestConstraintSystem::<Bls12>::new();
+
+                let a = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(Fr::from_repr(x.into()).unwrap())).unwrap();
+                let b = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(Fr::from_repr(y.into()).unwrap())).unwrap();
+
+                let lt = AllocatedNum::less_than(cs.namespace(|| "lt"), &a, &b, num_bits).unwrap();
+
+                assert!(cs.is_satisfied());
+                assert_eq!(cs.num_constraints(), num_bits + 2);
+                assert_eq!(lt.get_value().unwrap(), x < y);
+
+                // Flipping the result breaks the decomposition.
+                let path = format!("lt/bit {}/boolean", num_bits);
+                let prev = cs.get(&path);
+                let mut flipped = Fr::one();
+                flipped.sub_assign(&prev);
+                cs.set(&path, flipped);
+                assert_eq!(cs.which_is_unsatisfied().unwrap(), "lt/comparison constraint");
+            }
+        }
+    }
+
+    #[test]
+    fn test_assert_in_set() {
+        let allowed = [
+            Fr::from_str("3").unwrap(),
+            Fr::from_str("5").unwrap(),
+            Fr::from_str("7").unwrap()
+        ];
+
+        for &(value, ok) in &[("3", true), ("5", true), ("7", true), ("4", false), ("0", false)] {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let n = AllocatedNum::alloc(cs.namespace(|| "n"), || Ok(Fr::from_str(value).unwrap())).unwrap();
+            n.assert_in_set(cs.namespace(|| "in set"), &allowed).unwrap();
+
+            assert_eq!(cs.num_constraints(), 2);
+
+            if ok {
+                assert!(cs.is_satisfied());
+            } else {
+                assert_eq!(cs.which_is_unsatisfied().unwrap(), "in set/set membership constraint");
+            }
+        }
+
+        for &(value, ok) in &[("3", true), ("4", false)] {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let n = AllocatedNum::alloc(cs.namespace(|| "n"), || Ok(Fr::from_str(value).unwrap())).unwrap();
+            n.assert_in_set(cs.namespace(|| "in set"), &allowed[0..1]).unwrap();
+
+            assert_eq!(cs.num_constraints(), 1);
+            assert_eq!(cs.is_satisfied(), ok);
+        }
+    }
+
+    #[test]
+    fn test_assert_boolean() {
+        for &(value, ok) in &[("0", true), ("1", true), ("2", false)] {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let n = AllocatedNum::alloc(cs.namespace(|| "n"), || Ok(Fr::from_str(value).unwrap())).unwrap();
+            let b = n.assert_boolean(cs.namespace(|| "bit")).unwrap();
+
+            assert_eq!(cs.num_constraints(), 1);
+
+            if ok {
+                assert!(cs.is_satisfied());
+                assert_eq!(b.get_value().unwrap(), value == "1");
+            } else {
+                assert_eq!(cs.which_is_unsatisfied().unwrap(), "bit/boolean constraint");
+                assert_eq!(b.get_value(), None);
+            }
+
+            // The boolean shares the variable of the number.
+            match b {
+                Boolean::Is(ref bit) => {
+                    match (bit.get_variable(), n.get_variable()) {
+                        (Variable::Aux(a), Variable::Aux(b)) => assert_eq!(a, b),
+                        _ => panic!("expected the same variable")
+                    }
+                },
+                _ => panic!("expected an allocated boolean")
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_bits() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        let bits: Vec<bool> = (0..200).map(|_| rng.gen()).collect();
+
+        let mut expected = Fr::zero();
+        for &b in &bits {
+            expected.double();
+            if b {
+                expected.add_assign(&Fr::one());
+            }
+        }
+
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let bools = bits.iter().enumerate().map(|(i, b)| {
+            Boolean::from(AllocatedBit::alloc(cs.namespace(|| format!("bit {}", i)), Some(*b)).unwrap())
+        }).collect::<Vec<_>>();
+
+        let num = AllocatedNum::from_bits(cs.namespace(|| "packed"), &bools).unwrap();
+
+        assert!(cs.is_satisfied());
+        assert_eq!(num.value.unwrap(), expected);
+        assert_eq!(cs.num_constraints(), 200 + 1);
+
+        cs.set("packed/num", rng.gen());
+        assert_eq!(cs.which_is_unsatisfied().unwrap(), "packed/packing constraint");
+
+        // The strict variant needs the full width, and
+        // pays for the range check.
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let bools = (0..55).map(|_| Boolean::constant(false)).chain(
+            bits.iter().enumerate().map(|(i, b)| {
+                Boolean::from(AllocatedBit::alloc(cs.namespace(|| format!("bit {}", i)), Some(*b)).unwrap())
+            })
+        ).collect::<Vec<_>>();
+
+        let num = AllocatedNum::from_bits_strict(cs.namespace(|| "packed"), &bools).unwrap();
+
+        assert!(cs.is_satisfied());
+        assert_eq!(num.value.unwrap(), expected);
+        assert!(cs.num_constraints() > 200 + 1);
+
+        // Too many bits is an error rather than a panic.
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let bools = (0..256).map(|_| Boolean::constant(false)).collect::<Vec<_>>();
+
+        assert!(AllocatedNum::from_bits(cs.namespace(|| "too wide"), &bools).is_err());
+    }
+
     #[test]
     fn test_from_bits_strict() {
         {
@@ -705,4 +3190,221 @@ mod test {
             assert_eq!(cs.which_is_unsatisfied().unwrap(), "packing constraint");
         }
     }
+
+    #[test]
+    fn test_from_bits_strict_constant() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..100 {
+            let r = Fr::rand(&mut rng);
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let bits = BitIterator::new(r.into_repr()).skip(1)
+                                                      .map(|b| Boolean::constant(b))
+                                                      .collect::<Vec<_>>();
+
+            let num = AllocatedNum::from_bits_strict(&mut cs, &bits).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(num.value.unwrap(), r);
+            assert_eq!(cs.num_constraints(), 1);
+
+            cs.set("num", Fr::rand(&mut rng));
+            assert_eq!(cs.which_is_unsatisfied().unwrap(), "packing constraint");
+        }
+
+        // A constant outside of the field is rejected outright.
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+        let bits = BitIterator::new(Fr::char()).skip(1)
+                                               .map(|b| Boolean::constant(b))
+                                               .collect::<Vec<_>>();
+
+        assert!(AllocatedNum::from_bits_strict(&mut cs, &bits).is_err());
+
+        // So is the wrong number of bits, without
+        // adding anything to the constraint system.
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+        let bits = (0..100).map(|i| {
+            Boolean::from(AllocatedBit::alloc(cs.namespace(|| format!("bit {}", i)), Some(i % 3 == 0)).unwrap())
+        }).collect::<Vec<_>>();
+
+        assert!(AllocatedNum::from_bits_strict(cs.namespace(|| "short"), &bits).is_err());
+        assert_eq!(cs.num_constraints(), 100);
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_num_packing() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..100 {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let values: Vec<bool> = (0..16).map(|_| rng.gen()).collect();
+
+            let one = cs.one();
+            let mut num = Num::zero();
+            let mut coeff = Fr::one();
+            let mut expected = 0u64;
+
+            for (i, &b) in values.iter().enumerate() {
+                // Mix constants and allocated bits.
+                let bit = if i % 4 == 0 {
+                    Boolean::constant(b)
+                } else {
+                    Boolean::from(AllocatedBit::alloc(cs.namespace(|| format!("bit {}", i)), Some(b)).unwrap())
+                };
+
+                num = num.add_bool_with_coeff(one, &bit, coeff);
+                coeff.double();
+
+                if b {
+                    expected |= 1 << i;
+                }
+            }
+
+            let before = cs.num_constraints();
+            let packed = num.into_allocated_num(cs.namespace(|| "packed")).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(cs.num_constraints(), before + 1);
+            assert!(packed.get_value().unwrap() == Fr::from_str(&expected.to_string()).unwrap());
+
+            cs.set("packed/num", rng.gen());
+            assert_eq!(cs.which_is_unsatisfied(), Some("packed/packing constraint"));
+        }
+    }
+
+    #[test]
+    fn test_num_add_num() {
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let a = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(Fr::from_str("6").unwrap())).unwrap();
+        let b = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(Fr::from_str("5").unwrap())).unwrap();
+
+        // 2a + 3b + a = 33
+        let num = Num::from(a.clone())
+                      .add_num(&b, Fr::from_str("3").unwrap())
+                      .add_num(&a, Fr::from_str("2").unwrap());
+
+        assert!(num.get_value().unwrap() == Fr::from_str("33").unwrap());
+
+        let c = num.into_allocated_num(cs.namespace(|| "c")).unwrap();
+
+        assert!(cs.is_satisfied());
+        assert_eq!(cs.num_constraints(), 1);
+        assert!(c.get_value().unwrap() == Fr::from_str("33").unwrap());
+    }
+
+    #[test]
+    fn test_num_add_lc() {
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let a = AllocatedNum::alloc(cs.namespace(|| "a"), || Ok(Fr::from_str("6").unwrap())).unwrap();
+        let b = AllocatedNum::alloc(cs.namespace(|| "b"), || Ok(Fr::from_str("5").unwrap())).unwrap();
+
+        // a + 2(3b + a) = 48
+        let inner = Num::zero().add_num(&b, Fr::from_str("3").unwrap()).add_num(&a, Fr::one());
+        let num = Num::from(a.clone()).add_lc(&inner, Fr::from_str("2").unwrap());
+
+        assert!(num.get_value().unwrap() == Fr::from_str("48").unwrap());
+
+        let c = num.into_allocated_num(cs.namespace(|| "c")).unwrap();
+
+        assert!(cs.is_satisfied());
+        assert_eq!(cs.num_constraints(), 1);
+        assert!(c.get_value().unwrap() == Fr::from_str("48").unwrap());
+    }
+
+    #[test]
+    fn test_pack_into_nums() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for &(num_bits, num_nums) in &[(0, 0), (1, 1), (254, 1), (255, 2), (600, 3)] {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let values: Vec<bool> = (0..num_bits).map(|_| rng.gen()).collect();
+            let bits: Vec<_> = values.iter().enumerate().map(|(i, &b)| {
+                Boolean::from(AllocatedBit::alloc(cs.namespace(|| format!("bit {}", i)), Some(b)).unwrap())
+            }).collect();
+
+            let nums = pack_into_nums(cs.namespace(|| "pack"), &bits).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(nums.len(), num_nums);
+            assert_eq!(cs.num_constraints(), num_bits + num_nums);
+
+            for (num, chunk) in nums.iter().zip(values.chunks(Fr::CAPACITY as usize)) {
+                let mut expected = Fr::zero();
+                for &b in chunk.iter().rev() {
+                    expected.double();
+                    if b {
+                        expected.add_assign(&Fr::one());
+                    }
+                }
+
+                assert!(num.get_value().unwrap() == expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bits_to_num_lc() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..10 {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let value: Fr = rng.gen();
+            let n = AllocatedNum::alloc(cs.namespace(|| "n"), || Ok(value)).unwrap();
+            let bits = n.into_bits_strict(cs.namespace(|| "bits")).unwrap();
+            let num_constraints = cs.num_constraints();
+
+            let one = cs.one();
+
+            // Re-packing every bit gives back the number.
+            let all = bits_to_num_lc(one, bits.iter().rev());
+            assert!(all.get_value().unwrap() == value);
+            assert_eq!(cs.num_constraints(), num_constraints);
+
+            let all = all.into_allocated_num(cs.namespace(|| "all")).unwrap();
+            all.assert_equal(cs.namespace(|| "all equality"), &n).unwrap();
+
+            // Re-packing the low 64 bits gives the low limb.
+            let low = bits_to_num_lc::<Bls12, _, _>(one, bits.iter().rev().take(64))
+                          .into_allocated_num(cs.namespace(|| "low")).unwrap();
+
+            let mut expected = <Fr as PrimeField>::Repr::from(0);
+            expected.as_mut()[0] = value.into_repr().as_ref()[0];
+            assert!(low.get_value().unwrap() == Fr::from_repr(expected).unwrap());
+
+            assert!(cs.is_satisfied());
+            assert_eq!(cs.num_constraints(), num_constraints + 3);
+        }
+    }
+
+    #[test]
+    fn test_lsb() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for i in 0..100 {
+            let mut value = if i < 10 {
+                Fr::from_str(&i.to_string()).unwrap()
+            } else {
+                rng.gen()
+            };
+            if i == 10 {
+                value = Fr::zero();
+                value.sub_assign(&Fr::one());
+            }
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let n = AllocatedNum::alloc(cs.namespace(|| "n"), || Ok(value)).unwrap();
+            let lsb = n.lsb(cs.namespace(|| "lsb")).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(lsb.get_value().unwrap(), value.into_repr().is_odd());
+        }
+    }
 }
@@ -1,44 +1,84 @@
 use super::*;
-use super::mont::{
+use super::ecc::{
     MontgomeryPoint,
-    EdwardsPoint
+    EdwardsPoint,
+    fixed_base_multiplication
 };
+use super::num::AllocatedNum;
 use super::boolean::Boolean;
 use ::jubjub::*;
 use bellman::{
     ConstraintSystem
 };
+use pairing::PrimeField;
 use super::lookup::*;
+pub use pedersen_hash::Personalization;
 
+fn get_constant_bools<Var: Copy>(person: &Personalization) -> Vec<Boolean<Var>> {
+    person.get_bits()
+          .into_iter()
+          .map(|e| Boolean::constant(e))
+          .collect()
+}
+
+/// Computes the Pedersen hash of `bits` under the
+/// given personalization. The windows are named
+/// `"segment {}, window {}"` relative to `cs`, so
+/// hashes in the same circuit must each be given
+/// their own namespace; `TestConstraintSystem`
+/// panics if two of them share one.
 pub fn pedersen_hash<E: JubjubEngine, CS, Var: Copy>(
-    mut cs: CS,
+    cs: CS,
+    personalization: Personalization,
     bits: &[Boolean<Var>],
     params: &E::Params
 ) -> Result<EdwardsPoint<E, Var>, SynthesisError>
     where CS: ConstraintSystem<E, Variable=Var>
 {
-    // Unnecessary if forced personalization is introduced
-    assert!(bits.len() > 0);
+    pedersen_hash_stream(cs, personalization, bits.iter().cloned(), params)
+}
+
+/// Same as `pedersen_hash`, but consumes the bits from
+/// an iterator, so that they need not all be held in
+/// memory at once.
+pub fn pedersen_hash_stream<E: JubjubEngine, CS, Var: Copy, I>(
+    mut cs: CS,
+    personalization: Personalization,
+    bits: I,
+    params: &E::Params
+) -> Result<EdwardsPoint<E, Var>, SynthesisError>
+    where CS: ConstraintSystem<E, Variable=Var>,
+          I: IntoIterator<Item=Boolean<Var>>
+{
+    let personalization = get_constant_bools(&personalization);
 
     let mut edwards_result = None;
-    let mut bits = bits.iter();
+    let mut bits = personalization.into_iter().chain(bits).peekable();
     let mut segment_generators = params.pedersen_circuit_generators().iter();
-    let boolean_false = Boolean::constant(false);
 
     let mut segment_i = 0;
     loop {
+        if bits.peek().is_none() {
+            // There are no more bits to process.
+            break;
+        }
+
         let mut segment_result = None;
-        let mut segment_windows = &segment_generators.next()
-                                                     .expect("enough segments")[..];
+        // Running out of generators means the input was too long.
+        let mut segment_windows = match segment_generators.next() {
+            Some(windows) => &windows[..],
+            None => return Err(SynthesisError::AssignmentMissing)
+        };
 
         let mut window_i = 0;
         while let Some(a) = bits.next() {
-            let b = bits.next().unwrap_or(&boolean_false);
-            let c = bits.next().unwrap_or(&boolean_false);
+            let b = bits.next().unwrap_or(Boolean::constant(false));
+            let c = bits.next().unwrap_or(Boolean::constant(false));
 
-            let tmp = lookup3_xy_with_conditional_negation(
-                cs.namespace(|| format!("segment {}, window {}", segment_i, window_i)),
-                &[a.clone(), b.clone(), c.clone()],
+            let tmp = lookup3_xy_with_conditional_negation_indexed(
+                &mut cs,
+                (segment_i, window_i),
+                &[a, b, c],
                 &segment_windows[0]
             )?;
 
@@ -96,7 +136,137 @@ pub fn pedersen_hash<E: JubjubEngine, CS, Var: Copy>(
         segment_i += 1;
     }
 
-    Ok(edwards_result.unwrap())
+    Ok(edwards_result.get()?.clone())
+}
+
+/// Computes the Pedersen hash of `bits` and returns the
+/// low `E::Fs::CAPACITY` bits of the canonical
+/// x-coordinate, in little-endian order.
+pub fn pedersen_hash_to_scalar_bits<E: JubjubEngine, CS, Var: Copy>(
+    mut cs: CS,
+    personalization: Personalization,
+    bits: &[Boolean<Var>],
+    params: &E::Params
+) -> Result<Vec<Boolean<Var>>, SynthesisError>
+    where CS: ConstraintSystem<E, Variable=Var>
+{
+    let hash = pedersen_hash(
+        cs.namespace(|| "hash"),
+        personalization,
+        bits,
+        params
+    )?;
+
+    let mut x_bits = hash.x.into_bits_strict(cs.namespace(|| "x bits"))?;
+    x_bits.reverse();
+    x_bits.truncate(E::Fs::CAPACITY as usize);
+
+    Ok(x_bits)
+}
+
+/// Computes the Pedersen hash of `bits` and returns the
+/// compressed point, as produced by
+/// `EdwardsPoint::into_bits`.
+pub fn pedersen_hash_bits<E: JubjubEngine, CS, Var: Copy>(
+    mut cs: CS,
+    personalization: Personalization,
+    bits: &[Boolean<Var>],
+    params: &E::Params
+) -> Result<Vec<Boolean<Var>>, SynthesisError>
+    where CS: ConstraintSystem<E, Variable=Var>
+{
+    let hash = pedersen_hash(
+        cs.namespace(|| "hash"),
+        personalization,
+        bits,
+        params
+    )?;
+
+    hash.into_bits(cs.namespace(|| "point bits"))
+}
+
+/// Hashes `input` to `out_len` field elements. The input
+/// is absorbed with the `Sponge(0)` personalization, and
+/// output `i` is the hash of the absorbed state under
+/// `Sponge(i + 1)`, so at most 30 outputs are possible;
+/// asking for more is an error.
+pub fn sponge<E: JubjubEngine, CS, Var: Copy>(
+    mut cs: CS,
+    input: &[Boolean<Var>],
+    out_len: usize,
+    params: &E::Params
+) -> Result<Vec<AllocatedNum<E, Var>>, SynthesisError>
+    where CS: ConstraintSystem<E, Variable=Var>
+{
+    if out_len > 30 {
+        // There is no more specific error to return.
+        return Err(SynthesisError::AssignmentMissing);
+    }
+
+    if out_len == 0 {
+        return Ok(vec![]);
+    }
+
+    let state = pedersen_hash(
+        cs.namespace(|| "absorb"),
+        Personalization::Sponge(0),
+        input,
+        params
+    )?;
+
+    let mut state_bits = state.x.into_bits_strict(cs.namespace(|| "state bits"))?;
+    state_bits.reverse();
+
+    let mut outputs = vec![];
+    for i in 0..out_len {
+        let out = pedersen_hash(
+            cs.namespace(|| format!("squeeze {}", i)),
+            Personalization::Sponge(i + 1),
+            &state_bits,
+            params
+        )?;
+
+        outputs.push(out.x);
+    }
+
+    Ok(outputs)
+}
+
+/// Computes a windowed note commitment: the Pedersen
+/// hash of the note under `Personalization::NoteCommitment`,
+/// blinded by `rcm` times the note commitment randomization
+/// generator. `rcm` is in little-endian bit order and must
+/// be a multiple of 3 bits long. Returns the x-coordinate
+/// of the commitment.
+pub fn note_commitment_hash<E: JubjubEngine, CS, Var: Copy>(
+    mut cs: CS,
+    bits: &[Boolean<Var>],
+    rcm: &[Boolean<Var>],
+    params: &E::Params
+) -> Result<AllocatedNum<E, Var>, SynthesisError>
+    where CS: ConstraintSystem<E, Variable=Var>
+{
+    let cm = pedersen_hash(
+        cs.namespace(|| "note content hash"),
+        Personalization::NoteCommitment,
+        bits,
+        params
+    )?;
+
+    let rcm = fixed_base_multiplication(
+        cs.namespace(|| "computation of randomization for the note commitment"),
+        FixedGenerators::NoteCommitmentRandomization,
+        rcm,
+        params
+    )?;
+
+    let cm = cm.add(
+        cs.namespace(|| "randomization of note commitment"),
+        &rcm,
+        params
+    )?;
+
+    Ok(cm.into_num())
 }
 
 #[cfg(test)]
@@ -106,7 +276,8 @@ mod test {
     use ::circuit::test::*;
     use ::circuit::boolean::{Boolean, AllocatedBit};
     use pairing::bls12_381::{Bls12, Fr};
-    use pairing::PrimeField;
+    use pairing::{PrimeField, BitIterator};
+    use ::jubjub::fs::Fs;
 
     #[test]
     fn test_pedersen_hash_constraints() {
@@ -124,12 +295,13 @@ mod test {
 
         pedersen_hash(
             cs.namespace(|| "pedersen hash"),
+            Personalization::NoteCommitment,
             &input_bools,
             params
         ).unwrap();
 
         assert!(cs.is_satisfied());
-        assert_eq!(cs.num_constraints(), 1539);
+        assert_eq!(cs.num_constraints(), 1554);
     }
 
     #[test]
@@ -151,6 +323,7 @@ mod test {
 
                 let res = pedersen_hash(
                     cs.namespace(|| "pedersen hash"),
+                    Personalization::MerkleTree(1),
                     &input_bools,
                     params
                 ).unwrap();
@@ -158,6 +331,7 @@ mod test {
                 assert!(cs.is_satisfied());
 
                 let expected = ::pedersen_hash::pedersen_hash::<Bls12, _>(
+                    Personalization::MerkleTree(1),
                     input.into_iter(),
                     params
                 ).into_xy();
@@ -167,4 +341,419 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_pedersen_hash_toy_engine() {
+        use ::jubjub::toy::{Toy, JubjubToy};
+
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let params = &JubjubToy::new();
+
+        for length in &[1, 10, 100, 510] {
+            let input: Vec<bool> = (0..*length).map(|_| rng.gen()).collect();
+
+            let mut cs = TestConstraintSystem::<Toy>::new();
+
+            let input_bools: Vec<Boolean<_>> = input.iter().enumerate().map(|(i, b)| {
+                Boolean::from(
+                    AllocatedBit::alloc(cs.namespace(|| format!("input {}", i)), Some(*b)).unwrap()
+                )
+            }).collect();
+
+            let res = pedersen_hash(
+                cs.namespace(|| "pedersen hash"),
+                Personalization::MerkleTree(1),
+                &input_bools,
+                params
+            ).unwrap();
+
+            assert!(cs.is_satisfied());
+
+            let expected = ::pedersen_hash::pedersen_hash::<Toy, _>(
+                Personalization::MerkleTree(1),
+                input.iter().cloned(),
+                params
+            ).into_xy();
+
+            assert_eq!(res.x.get_value().unwrap(), expected.0);
+            assert_eq!(res.y.get_value().unwrap(), expected.1);
+
+            // The toy engine embeds the same curve, so the
+            // hash agrees with the one over Bls12.
+            let bls12 = ::pedersen_hash::pedersen_hash::<Bls12, _>(
+                Personalization::MerkleTree(1),
+                input.into_iter(),
+                &JubjubBls12::new()
+            ).into_xy();
+
+            assert_eq!(expected, bls12);
+        }
+    }
+
+    #[test]
+    fn test_pedersen_hash_stream() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let params = &JubjubBls12::new();
+
+        let bits_per_segment = params.pedersen_hash_chunks_per_generator() * 3;
+        let max_bits = params.pedersen_circuit_generators().len() * bits_per_segment - 6;
+
+        // Lengths on either side of window and segment
+        // boundaries, up to the longest possible input.
+        let mut lengths = vec![0, 1, 2, 3, 4, max_bits];
+        for i in 1..4 {
+            let boundary = i * bits_per_segment - 6;
+            lengths.extend(&[boundary - 1, boundary, boundary + 1]);
+        }
+
+        for length in lengths {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let input_bools: Vec<Boolean<_>> = (0..length).map(|i| {
+                Boolean::from(
+                    AllocatedBit::alloc(cs.namespace(|| format!("input {}", i)), Some(rng.gen())).unwrap()
+                )
+            }).collect();
+
+            let before = cs.num_constraints();
+
+            let res1 = pedersen_hash(
+                cs.namespace(|| "pedersen hash"),
+                Personalization::MerkleTree(3),
+                &input_bools,
+                params
+            ).unwrap();
+
+            let cost = cs.num_constraints() - before;
+
+            let res2 = pedersen_hash_stream(
+                cs.namespace(|| "pedersen hash stream"),
+                Personalization::MerkleTree(3),
+                input_bools.into_iter(),
+                params
+            ).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(cs.num_constraints() - before, cost * 2);
+            assert_eq!(res1.x.get_value().unwrap(), res2.x.get_value().unwrap());
+            assert_eq!(res1.y.get_value().unwrap(), res2.y.get_value().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_pedersen_hash_empty_input() {
+        let params = &JubjubBls12::new();
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let res = pedersen_hash::<Bls12, _, _>(
+            cs.namespace(|| "pedersen hash"),
+            Personalization::MerkleTree(0),
+            &[],
+            params
+        ).unwrap();
+
+        assert!(cs.is_satisfied());
+
+        // The personalization is always hashed, so the empty
+        // input is well-defined and agrees with the native hash.
+        let expected = ::pedersen_hash::pedersen_hash::<Bls12, _>(
+            Personalization::MerkleTree(0),
+            vec![],
+            params
+        ).into_xy();
+
+        assert_eq!(res.x.get_value().unwrap(), expected.0);
+        assert_eq!(res.y.get_value().unwrap(), expected.1);
+    }
+
+    #[test]
+    fn test_pedersen_hash_too_long() {
+        let params = &JubjubBls12::new();
+
+        // The number of bits the generators can absorb,
+        // less the 6 bits of personalization.
+        let max_bits = params.pedersen_circuit_generators()
+                             .iter()
+                             .map(|windows| windows.len() * 3)
+                             .sum::<usize>() - 6;
+
+        for &(length, ok) in &[(max_bits, true), (max_bits + 1, false)] {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let input_bools: Vec<Boolean<_>> = (0..length).map(|i| {
+                Boolean::from(
+                    AllocatedBit::alloc(cs.namespace(|| format!("input {}", i)), Some(i % 3 == 0)).unwrap()
+                )
+            }).collect();
+
+            let res = pedersen_hash(
+                cs.namespace(|| "pedersen hash"),
+                Personalization::MerkleTree(0),
+                &input_bools,
+                params
+            );
+
+            assert_eq!(res.is_ok(), ok);
+        }
+    }
+
+    #[test]
+    fn test_pedersen_hash_personalization() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let params = &JubjubBls12::new();
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let input: Vec<bool> = (0..100).map(|_| rng.gen()).collect();
+
+        let input_bools: Vec<Boolean<_>> = input.iter().enumerate().map(|(i, b)| {
+            Boolean::from(
+                AllocatedBit::alloc(cs.namespace(|| format!("input {}", i)), Some(*b)).unwrap()
+            )
+        }).collect();
+
+        let res1 = pedersen_hash(
+            cs.namespace(|| "pedersen hash 1"),
+            Personalization::NoteCommitment,
+            &input_bools,
+            params
+        ).unwrap();
+
+        let res2 = pedersen_hash(
+            cs.namespace(|| "pedersen hash 2"),
+            Personalization::MerkleTree(0),
+            &input_bools,
+            params
+        ).unwrap();
+
+        res1.assert_not_identity(cs.namespace(|| "hash 1 is not identity")).unwrap();
+        res2.assert_not_identity(cs.namespace(|| "hash 2 is not identity")).unwrap();
+
+        assert!(cs.is_satisfied());
+        assert!(res1.x.get_value().unwrap() != res2.x.get_value().unwrap());
+    }
+
+    #[test]
+    fn test_pedersen_hash_to_scalar_bits() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let params = &JubjubBls12::new();
+
+        for length in &[0, 1, 100, 300] {
+            let input: Vec<bool> = (0..*length).map(|_| rng.gen()).collect();
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let input_bools: Vec<Boolean<_>> = input.iter().enumerate().map(|(i, b)| {
+                Boolean::from(
+                    AllocatedBit::alloc(cs.namespace(|| format!("input {}", i)), Some(*b)).unwrap()
+                )
+            }).collect();
+
+            let res = pedersen_hash_to_scalar_bits(
+                cs.namespace(|| "pedersen hash"),
+                Personalization::MerkleTree(0),
+                &input_bools,
+                params
+            ).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(res.len(), Fs::CAPACITY as usize);
+
+            let x = ::pedersen_hash::pedersen_hash::<Bls12, _>(
+                Personalization::MerkleTree(0),
+                input.into_iter(),
+                params
+            ).into_xy().0;
+
+            let mut expected = BitIterator::new(x.into_repr()).collect::<Vec<_>>();
+            expected.reverse();
+            expected.truncate(Fs::CAPACITY as usize);
+
+            assert_eq!(res.iter().map(|b| b.get_value().unwrap()).collect::<Vec<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn test_pedersen_hash_bits() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let params = &JubjubBls12::new();
+
+        for length in &[0, 1, 100, 300] {
+            let input: Vec<bool> = (0..*length).map(|_| rng.gen()).collect();
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let input_bools: Vec<Boolean<_>> = input.iter().enumerate().map(|(i, b)| {
+                Boolean::from(
+                    AllocatedBit::alloc(cs.namespace(|| format!("input {}", i)), Some(*b)).unwrap()
+                )
+            }).collect();
+
+            let res = pedersen_hash_bits(
+                cs.namespace(|| "combined"),
+                Personalization::MerkleTree(0),
+                &input_bools,
+                params
+            ).unwrap();
+            let combined_constraints = cs.num_constraints() - length;
+
+            let manual = pedersen_hash(
+                cs.namespace(|| "hash"),
+                Personalization::MerkleTree(0),
+                &input_bools,
+                params
+            ).unwrap().into_bits(cs.namespace(|| "bits")).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(res.len(), 256);
+            assert_eq!(
+                res.iter().map(|b| b.get_value().unwrap()).collect::<Vec<_>>(),
+                manual.iter().map(|b| b.get_value().unwrap()).collect::<Vec<_>>()
+            );
+            assert_eq!(cs.num_constraints(), length + 2 * combined_constraints);
+        }
+    }
+
+    #[test]
+    fn test_sponge() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let params = &JubjubBls12::new();
+
+        let input: Vec<bool> = (0..300).map(|_| rng.gen()).collect();
+
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let input_bools: Vec<Boolean<_>> = input.iter().enumerate().map(|(i, b)| {
+            Boolean::from(
+                AllocatedBit::alloc(cs.namespace(|| format!("input {}", i)), Some(*b)).unwrap()
+            )
+        }).collect();
+
+        let before = cs.num_constraints();
+        let empty = sponge(cs.namespace(|| "empty sponge"), &input_bools, 0, params).unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(cs.num_constraints(), before);
+
+        // Only 30 squeeze personalizations exist.
+        assert!(sponge(cs.namespace(|| "long sponge"), &input_bools, 31, params).is_err());
+        assert_eq!(cs.num_constraints(), before);
+
+        let out1 = sponge(cs.namespace(|| "sponge 1"), &input_bools, 3, params).unwrap();
+        let out2 = sponge(cs.namespace(|| "sponge 2"), &input_bools, 3, params).unwrap();
+
+        assert!(cs.is_satisfied());
+
+        let out1 = out1.iter().map(|n| n.get_value().unwrap()).collect::<Vec<_>>();
+        let out2 = out2.iter().map(|n| n.get_value().unwrap()).collect::<Vec<_>>();
+
+        // Deterministic, with distinct outputs.
+        assert_eq!(out1, out2);
+        assert!(out1[0] != out1[1]);
+        assert!(out1[1] != out1[2]);
+        assert!(out1[0] != out1[2]);
+
+        // Agrees with the native construction.
+        let state = ::pedersen_hash::pedersen_hash::<Bls12, _>(
+            Personalization::Sponge(0),
+            input.into_iter(),
+            params
+        ).into_xy().0;
+
+        let mut state_bits = BitIterator::new(state.into_repr()).collect::<Vec<_>>();
+        state_bits.reverse();
+        state_bits.truncate(Fr::NUM_BITS as usize);
+
+        for (i, out) in out1.iter().enumerate() {
+            let expected = ::pedersen_hash::pedersen_hash::<Bls12, _>(
+                Personalization::Sponge(i + 1),
+                state_bits.iter().cloned(),
+                params
+            ).into_xy().0;
+
+            assert_eq!(*out, expected);
+        }
+    }
+
+    #[test]
+    fn test_note_commitment_hash() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let params = &JubjubBls12::new();
+
+        for _ in 0..10 {
+            let input: Vec<bool> = (0..200).map(|_| rng.gen()).collect();
+            let rcm: Fs = rng.gen();
+
+            let mut rcm_bits = BitIterator::new(rcm.into_repr()).collect::<Vec<_>>();
+            rcm_bits.reverse();
+            rcm_bits.truncate(Fs::NUM_BITS as usize);
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let input_bools: Vec<Boolean<_>> = input.iter().enumerate().map(|(i, b)| {
+                Boolean::from(
+                    AllocatedBit::alloc(cs.namespace(|| format!("input {}", i)), Some(*b)).unwrap()
+                )
+            }).collect();
+
+            let rcm_bools: Vec<Boolean<_>> = rcm_bits.iter().enumerate().map(|(i, b)| {
+                Boolean::from(
+                    AllocatedBit::alloc(cs.namespace(|| format!("rcm {}", i)), Some(*b)).unwrap()
+                )
+            }).collect();
+
+            let res = note_commitment_hash(
+                cs.namespace(|| "note commitment"),
+                &input_bools,
+                &rcm_bools,
+                params
+            ).unwrap();
+
+            assert!(cs.is_satisfied());
+
+            let expected = ::primitives::NoteCommitment::<Bls12> {
+                content: input,
+                randomness: rcm
+            }.cm(params).into_xy();
+
+            assert_eq!(res.get_value().unwrap(), expected.0);
+        }
+    }
+
+    fn hash_twice(namespaces: (&'static str, &'static str)) -> TestConstraintSystem<Bls12> {
+        let params = &JubjubBls12::new();
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let input_bools: Vec<Boolean<_>> = (0..10).map(|i| {
+            Boolean::from(
+                AllocatedBit::alloc(cs.namespace(|| format!("input {}", i)), Some(i % 3 == 0)).unwrap()
+            )
+        }).collect();
+
+        for (i, namespace) in [namespaces.0, namespaces.1].iter().enumerate() {
+            pedersen_hash(
+                cs.namespace(|| *namespace),
+                Personalization::MerkleTree(i),
+                &input_bools,
+                params
+            ).unwrap();
+        }
+
+        cs
+    }
+
+    #[test]
+    fn test_pedersen_hash_composition() {
+        let mut cs = hash_twice(("left hash", "right hash"));
+
+        assert!(cs.is_satisfied());
+
+        // Both hashes have their own copy of each window.
+        cs.get("left hash/segment 0, window 0/y/num");
+        cs.get("right hash/segment 0, window 0/y/num");
+    }
+
+    #[test]
+    #[should_panic(expected = "tried to create object at existing path")]
+    fn test_pedersen_hash_shared_namespace() {
+        hash_twice(("hash", "hash"));
+    }
 }
@@ -0,0 +1,251 @@
+use pairing::{
+    Engine,
+    Field,
+    PrimeField
+};
+
+use bellman::{
+    SynthesisError,
+    ConstraintSystem,
+    LinearCombination
+};
+
+use super::boolean::Boolean;
+use super::num::{AllocatedNum, Num};
+use ::poseidon::PoseidonParams;
+
+/// Computes x^5, at the cost of three constraints. As
+/// `x` is a linear combination, any round constant added
+/// to it is folded into the constraints for free.
+fn sbox<E, CS, Var: Copy>(
+    mut cs: CS,
+    x: &Num<E, Var>
+) -> Result<AllocatedNum<E, Var>, SynthesisError>
+    where E: Engine, CS: ConstraintSystem<E, Variable=Var>
+{
+    let x2 = {
+        let mut cs = cs.namespace(|| "x^2");
+
+        let x2 = AllocatedNum::alloc(&mut cs, || {
+            let mut tmp = x.get_value().ok_or(SynthesisError::AssignmentMissing)?;
+            tmp.square();
+
+            Ok(tmp)
+        })?;
+
+        cs.enforce(
+            || "squaring constraint",
+            x.lc(E::Fr::one()),
+            x.lc(E::Fr::one()),
+            LinearCombination::zero() + x2.get_variable()
+        );
+
+        x2
+    };
+
+    let x4 = x2.square(cs.namespace(|| "x^4"))?;
+
+    let mut cs = cs.namespace(|| "x^5");
+
+    let x5 = AllocatedNum::alloc(&mut cs, || {
+        let mut tmp = x4.get_value().ok_or(SynthesisError::AssignmentMissing)?;
+        tmp.mul_assign(&x.get_value().ok_or(SynthesisError::AssignmentMissing)?);
+
+        Ok(tmp)
+    })?;
+
+    cs.enforce(
+        || "multiplication constraint",
+        LinearCombination::zero() + x4.get_variable(),
+        x.lc(E::Fr::one()),
+        LinearCombination::zero() + x5.get_variable()
+    );
+
+    Ok(x5)
+}
+
+/// A state element, held as a constant plus coefficients
+/// over a shared basis. Mixing coefficients rather than
+/// linear combinations keeps repeated terms from piling
+/// up across rounds.
+#[derive(Clone)]
+struct Lane<E: Engine> {
+    coeffs: Vec<E::Fr>,
+    constant: E::Fr
+}
+
+impl<E: Engine> Lane<E> {
+    fn basis(len: usize, index: usize) -> Self {
+        let mut coeffs = vec![E::Fr::zero(); len];
+        coeffs[index] = E::Fr::one();
+
+        Lane {
+            coeffs: coeffs,
+            constant: E::Fr::zero()
+        }
+    }
+
+    fn into_num<Var: Copy>(&self, one: Var, basis: &[Num<E, Var>]) -> Num<E, Var> {
+        let num = basis.iter().zip(self.coeffs.iter())
+                       .filter(|&(_, c)| !c.is_zero())
+                       .fold(Num::zero(), |acc, (b, c)| acc.add_lc(b, *c));
+
+        num.add_bool_with_coeff(one, &Boolean::constant(true), self.constant)
+    }
+}
+
+/// Applies the Poseidon permutation to a state of
+/// linear combinations. Only the s-box outputs are
+/// allocated, so a partial round costs just the three
+/// constraints of its single s-box, and the returned
+/// state is left unallocated.
+pub fn poseidon_permutation<E, CS, Var: Copy>(
+    mut cs: CS,
+    state: Vec<Num<E, Var>>,
+    params: &PoseidonParams<E>
+) -> Result<Vec<Num<E, Var>>, SynthesisError>
+    where E: Engine, CS: ConstraintSystem<E, Variable=Var>
+{
+    assert_eq!(state.len(), params.t());
+
+    let one = cs.one();
+    let t = params.t();
+
+    let mut basis = state;
+    let mut lanes: Vec<Lane<E>> = (0..t).map(|i| Lane::basis(t, i)).collect();
+
+    for round in 0..params.num_rounds() {
+        let mut cs = cs.namespace(|| format!("round {}", round));
+
+        for (lane, c) in lanes.iter_mut().zip(params.round_constants(round)) {
+            lane.constant.add_assign(c);
+        }
+
+        if params.is_full_round(round) {
+            let mut outputs = Vec::with_capacity(t);
+            for (i, lane) in lanes.iter().enumerate() {
+                let x = lane.into_num(one, &basis);
+                outputs.push(Num::from(sbox(cs.namespace(|| format!("s-box {}", i)), &x)?));
+            }
+
+            basis = outputs;
+            lanes = (0..t).map(|i| Lane::basis(t, i)).collect();
+        } else {
+            let x = lanes[0].into_num(one, &basis);
+            basis.push(Num::from(sbox(cs.namespace(|| "s-box 0"), &x)?));
+
+            for lane in lanes.iter_mut() {
+                lane.coeffs.push(E::Fr::zero());
+            }
+            lanes[0] = Lane::basis(basis.len(), basis.len() - 1);
+        }
+
+        lanes = params.mds().iter().map(|row| {
+            let mut acc: Lane<E> = Lane {
+                coeffs: vec![E::Fr::zero(); basis.len()],
+                constant: E::Fr::zero()
+            };
+
+            for (m, lane) in row.iter().zip(lanes.iter()) {
+                for (a, c) in acc.coeffs.iter_mut().zip(lane.coeffs.iter()) {
+                    let mut tmp = *c;
+                    tmp.mul_assign(m);
+                    a.add_assign(&tmp);
+                }
+
+                let mut tmp = lane.constant;
+                tmp.mul_assign(m);
+                acc.constant.add_assign(&tmp);
+            }
+
+            acc
+        }).collect();
+    }
+
+    Ok(lanes.iter().map(|lane| lane.into_num(one, &basis)).collect())
+}
+
+/// Hashes `inputs` with a sponge over the permutation,
+/// matching `poseidon::poseidon_hash`.
+pub fn poseidon_hash<E, CS, Var: Copy>(
+    mut cs: CS,
+    inputs: &[AllocatedNum<E, Var>],
+    params: &PoseidonParams<E>
+) -> Result<AllocatedNum<E, Var>, SynthesisError>
+    where E: Engine, CS: ConstraintSystem<E, Variable=Var>
+{
+    assert!(inputs.len() > 0);
+
+    let one = cs.one();
+
+    let mut state: Vec<Num<E, Var>> = (0..params.t()).map(|_| Num::zero()).collect();
+    state[0] = Num::zero().add_bool_with_coeff(
+        one,
+        &Boolean::constant(true),
+        E::Fr::from_repr((inputs.len() as u64).into()).unwrap()
+    );
+
+    for (i, chunk) in inputs.chunks(params.t() - 1).enumerate() {
+        let mut tmp = state.into_iter();
+        let mut absorbed = vec![tmp.next().unwrap()];
+        for (s, input) in tmp.zip(chunk.iter().map(Some).chain(::std::iter::repeat(None))) {
+            absorbed.push(match input {
+                Some(input) => s.add_num(input, E::Fr::one()),
+                None => s
+            });
+        }
+
+        state = poseidon_permutation(
+            cs.namespace(|| format!("permutation {}", i)),
+            absorbed,
+            params
+        )?;
+    }
+
+    state.swap_remove(1).into_allocated_num(cs.namespace(|| "result"))
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{SeedableRng, Rand, XorShiftRng};
+    use bellman::ConstraintSystem;
+    use pairing::bls12_381::{Bls12, Fr};
+    use ::circuit::test::*;
+    use ::circuit::num::AllocatedNum;
+    use ::poseidon::{self, PoseidonParams};
+    use super::poseidon_hash;
+
+    #[test]
+    fn test_poseidon_hash() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let params = PoseidonParams::<Bls12>::new(3, 8, 57);
+
+        for num_inputs in 1..6 {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let values: Vec<Fr> = (0..num_inputs).map(|_| Fr::rand(&mut rng)).collect();
+            let inputs: Vec<_> = values.iter().enumerate().map(|(i, v)| {
+                AllocatedNum::alloc(cs.namespace(|| format!("input {}", i)), || Ok(*v)).unwrap()
+            }).collect();
+
+            let hash = poseidon_hash(cs.namespace(|| "poseidon"), &inputs, &params).unwrap();
+
+            assert!(cs.is_satisfied());
+            assert_eq!(hash.get_value().unwrap(), poseidon::poseidon_hash(&values, &params));
+
+            // Each s-box costs three constraints: one per
+            // element in a full round, and just one in a
+            // partial round.
+            let permutations = (num_inputs + 1) / 2;
+            assert_eq!(cs.num_constraints(), permutations * (8 * 3 * 3 + 57 * 3) + 1);
+
+            // Tampering with an s-box is caught.
+            let path = "poseidon/permutation 0/round 0/s-box 1/x^5/num";
+            let honest = cs.get(path);
+            cs.set(path, Fr::rand(&mut rng));
+            assert_eq!(cs.which_is_unsatisfied(), Some("poseidon/permutation 0/round 0/s-box 1/x^5/multiplication constraint"));
+            cs.set(path, honest);
+            assert!(cs.is_satisfied());
+        }
+    }
+}
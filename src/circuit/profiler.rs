@@ -0,0 +1,206 @@
+use pairing::Engine;
+
+use bellman::{
+    LinearCombination,
+    SynthesisError,
+    ConstraintSystem
+};
+
+use std::collections::HashMap;
+
+/// The constraints and variables contributed by
+/// everything inside a namespace.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Count {
+    pub constraints: usize,
+    pub variables: usize
+}
+
+/// Wraps a constraint system and records how many
+/// constraints and variables are added under each
+/// namespace. Counts are cumulative: a namespace's
+/// count includes all of its subnamespaces, and the
+/// empty path holds the total.
+pub struct ConstraintProfiler<CS> {
+    inner: CS,
+    current_namespace: Vec<String>,
+    counts: HashMap<String, Count>
+}
+
+impl<CS> ConstraintProfiler<CS> {
+    pub fn new(inner: CS) -> Self {
+        ConstraintProfiler {
+            inner: inner,
+            current_namespace: vec![],
+            counts: HashMap::new()
+        }
+    }
+
+    /// Returns the count for the namespace at `path`,
+    /// e.g. "pedersen hash/segment 0, window 0".
+    pub fn get(&self, path: &str) -> Count {
+        self.counts.get(path).cloned().unwrap_or_default()
+    }
+
+    /// Returns the counts of the direct subnamespaces
+    /// of `path`, keyed by their names.
+    pub fn children(&self, path: &str) -> HashMap<String, Count> {
+        let depth = if path.is_empty() { 0 } else { path.split('/').count() };
+
+        self.counts.iter()
+                   .filter(|&(p, _)| {
+                       !p.is_empty() &&
+                       p.split('/').count() == depth + 1 &&
+                       (depth == 0 || p.starts_with(&format!("{}/", path)))
+                   })
+                   .map(|(p, c)| (p.split('/').last().unwrap().to_owned(), *c))
+                   .collect()
+    }
+
+    pub fn counts(&self) -> &HashMap<String, Count> {
+        &self.counts
+    }
+
+    pub fn into_inner(self) -> CS {
+        self.inner
+    }
+
+    fn record<F: Fn(&mut Count)>(&mut self, f: F) {
+        let mut path = String::new();
+        f(self.counts.entry(path.clone()).or_insert(Count::default()));
+
+        for ns in self.current_namespace.iter() {
+            if !path.is_empty() {
+                path += "/";
+            }
+            path += ns;
+
+            f(self.counts.entry(path.clone()).or_insert(Count::default()));
+        }
+    }
+}
+
+impl<E: Engine, CS: ConstraintSystem<E>> ConstraintSystem<E> for ConstraintProfiler<CS> {
+    type Variable = CS::Variable;
+    type Root = Self;
+
+    fn one(&self) -> Self::Variable {
+        self.inner.one()
+    }
+
+    fn alloc<F, A, AR>(
+        &mut self,
+        annotation: A,
+        f: F
+    ) -> Result<Self::Variable, SynthesisError>
+        where F: FnOnce() -> Result<E::Fr, SynthesisError>, A: FnOnce() -> AR, AR: Into<String>
+    {
+        let var = self.inner.alloc(annotation, f)?;
+        self.record(|c| c.variables += 1);
+
+        Ok(var)
+    }
+
+    fn enforce<A, AR>(
+        &mut self,
+        annotation: A,
+        a: LinearCombination<Self::Variable, E>,
+        b: LinearCombination<Self::Variable, E>,
+        c: LinearCombination<Self::Variable, E>
+    )
+        where A: FnOnce() -> AR, AR: Into<String>
+    {
+        self.inner.enforce(annotation, a, b, c);
+        self.record(|c| c.constraints += 1);
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+        where NR: Into<String>, N: FnOnce() -> NR
+    {
+        let name = name_fn().into();
+        self.inner.get_root().push_namespace(|| name.clone());
+        self.current_namespace.push(name);
+    }
+
+    fn pop_namespace(&mut self)
+    {
+        self.inner.get_root().pop_namespace();
+        assert!(self.current_namespace.pop().is_some());
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root
+    {
+        self
+    }
+}
+
+// These tests only run with `cargo test --features profiler`.
+#[cfg(test)]
+mod test {
+    use rand::{SeedableRng, Rng, XorShiftRng};
+    use bellman::ConstraintSystem;
+    use pairing::bls12_381::Bls12;
+    use ::circuit::test::*;
+    use ::circuit::boolean::{Boolean, AllocatedBit};
+    use ::circuit::pedersen_hash::{pedersen_hash, Personalization};
+    use ::jubjub::JubjubBls12;
+    use std::collections::HashMap;
+    use super::{ConstraintProfiler, Count};
+
+    #[test]
+    fn test_profile_pedersen_hash() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let params = &JubjubBls12::new();
+        let input: Vec<bool> = (0..512).map(|_| rng.gen()).collect();
+
+        fn hash<CS: ConstraintSystem<Bls12>>(mut cs: CS, input: &[bool], params: &JubjubBls12) {
+            let input_bools: Vec<Boolean<_>> = input.iter().enumerate().map(|(i, b)| {
+                Boolean::from(
+                    AllocatedBit::alloc(cs.namespace(|| format!("input {}", i)), Some(*b)).unwrap()
+                )
+            }).collect();
+
+            pedersen_hash(
+                cs.namespace(|| "pedersen hash"),
+                Personalization::NoteCommitment,
+                &input_bools,
+                params
+            ).unwrap();
+        }
+
+        // The profiler must agree with the constraint
+        // system it wraps, whatever the hash costs.
+        let mut unprofiled = TestConstraintSystem::<Bls12>::new();
+        hash(&mut unprofiled, &input, params);
+        let expected = unprofiled.num_constraints();
+
+        let mut cs = ConstraintProfiler::new(TestConstraintSystem::<Bls12>::new());
+        hash(&mut cs, &input, params);
+
+        let total = cs.get("pedersen hash");
+        assert_eq!(total.constraints, expected - 512);
+        assert_eq!(cs.get("").constraints, expected);
+
+        // Group the work of the hash by segment; the
+        // additions to the accumulator belong to the
+        // segment being added.
+        let mut segments: HashMap<usize, Count> = HashMap::new();
+        for (name, count) in cs.children("pedersen hash") {
+            let segment = name.split("segment ").nth(1).unwrap()
+                              .split(|c: char| !c.is_digit(10)).next().unwrap()
+                              .parse::<usize>().unwrap();
+
+            let entry = segments.entry(segment).or_insert(Count::default());
+            entry.constraints += count.constraints;
+            entry.variables += count.variables;
+        }
+
+        assert_eq!(segments.len(), 3);
+        assert_eq!(segments.values().map(|c| c.constraints).sum::<usize>(), total.constraints);
+        assert_eq!(segments.values().map(|c| c.variables).sum::<usize>(), total.variables);
+
+        let cs = cs.into_inner();
+        assert!(cs.is_satisfied());
+        assert_eq!(cs.num_constraints(), expected);
+    }
+}
@@ -0,0 +1,107 @@
+use bellman::{
+    SynthesisError,
+    ConstraintSystem
+};
+
+use jubjub::JubjubEngine;
+
+use super::boolean::Boolean;
+use super::blake2s::blake2s;
+use super::ecc::EdwardsPoint;
+use ::primitives::NULLIFIER_PERSONALIZATION;
+
+/// Computes the nullifier `BLAKE2s(repr(nk) || rho)`,
+/// matching `primitives::nullifier`. Like the input
+/// to `blake2s`, `rho` and the returned 256 bits are
+/// whole bytes, each most significant bit first.
+pub fn nullifier<E: JubjubEngine, CS, Var: Copy>(
+    mut cs: CS,
+    nk: &EdwardsPoint<E, Var>,
+    rho: &[Boolean<Var>],
+    _params: &E::Params
+) -> Result<Vec<Boolean<Var>>, SynthesisError>
+    where CS: ConstraintSystem<E, Variable=Var>
+{
+    assert!(rho.len() % 8 == 0);
+
+    // The encoding of nk is little-endian, so each
+    // of its bytes must be reversed for BLAKE2s.
+    let nk_bits = nk.into_bits(cs.namespace(|| "nk representation"))?;
+
+    let mut preimage = vec![];
+    for byte in nk_bits.chunks(8) {
+        preimage.extend(byte.iter().rev().cloned());
+    }
+    preimage.extend(rho.iter().cloned());
+
+    blake2s(
+        cs.namespace(|| "nf computation"),
+        &preimage,
+        NULLIFIER_PERSONALIZATION
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{SeedableRng, Rng, XorShiftRng};
+    use bellman::ConstraintSystem;
+    use pairing::bls12_381::Bls12;
+    use ::circuit::test::*;
+    use ::circuit::boolean::{Boolean, AllocatedBit};
+    use ::circuit::num::AllocatedNum;
+    use ::circuit::ecc::EdwardsPoint;
+    use ::jubjub::{JubjubBls12, FixedGenerators, JubjubParams};
+    use ::jubjub::fs::Fs;
+    use ::primitives;
+    use super::nullifier;
+
+    #[test]
+    fn test_nullifier() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let params = &JubjubBls12::new();
+
+        for _ in 0..5 {
+            let nk = params.generator(FixedGenerators::NoteCommitmentRandomization)
+                           .mul(rng.gen::<Fs>(), params);
+            let rho: Vec<u8> = (0..32).map(|_| rng.gen()).collect();
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let (x, y) = nk.into_xy();
+            let nk_point = EdwardsPoint {
+                x: AllocatedNum::alloc(cs.namespace(|| "nk x"), || Ok(x)).unwrap(),
+                y: AllocatedNum::alloc(cs.namespace(|| "nk y"), || Ok(y)).unwrap()
+            };
+
+            let mut rho_bools = vec![];
+            for (byte_i, byte) in rho.iter().enumerate() {
+                for bit_i in (0..8).rev() {
+                    rho_bools.push(Boolean::from(
+                        AllocatedBit::alloc(
+                            cs.namespace(|| format!("rho bit {} {}", byte_i, bit_i)),
+                            Some((byte >> bit_i) & 1 == 1)
+                        ).unwrap()
+                    ));
+                }
+            }
+
+            let nf = nullifier(
+                cs.namespace(|| "nullifier"),
+                &nk_point,
+                &rho_bools,
+                params
+            ).unwrap();
+
+            assert!(cs.is_satisfied());
+
+            let expected = primitives::nullifier(&nk, &rho);
+            let expected = expected.iter()
+                                   .flat_map(|byte| (0..8).rev().map(move |i| (byte >> i) & 1 == 1));
+
+            assert_eq!(nf.len(), 256);
+            for (b, e) in nf.iter().zip(expected) {
+                assert_eq!(b.get_value().unwrap(), e);
+            }
+        }
+    }
+}
@@ -0,0 +1,315 @@
+use pairing::{
+    Engine,
+};
+
+use bellman::{
+    SynthesisError,
+    ConstraintSystem
+};
+
+use super::boolean::{
+    Boolean
+};
+
+use super::uint32::{
+    UInt32
+};
+
+const ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2
+];
+
+const IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a,
+    0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19
+];
+
+/// Computes the SHA-256 hash of `input`, as specified
+/// in FIPS 180-4. The input is a string of bits, most
+/// significant bit of each byte first, and need not be
+/// a whole number of bytes. The 256-bit digest is
+/// returned in the same order.
+pub fn sha256<E: Engine, CS: ConstraintSystem<E>>(
+    mut cs: CS,
+    input: &[Boolean<CS::Variable>]
+) -> Result<Vec<Boolean<CS::Variable>>, SynthesisError>
+{
+    // Padding: a one bit, zeroes up to 448 bits mod 512,
+    // then the length in bits as a big-endian u64.
+    let mut padded = input.to_vec();
+    padded.push(Boolean::constant(true));
+    while padded.len() % 512 != 448 {
+        padded.push(Boolean::constant(false));
+    }
+    let length = input.len() as u64;
+    for i in (0..64).rev() {
+        padded.push(Boolean::constant((length >> i) & 1 == 1));
+    }
+
+    let mut h: Vec<_> = IV.iter().map(|&v| UInt32::constant(v)).collect();
+
+    for (i, block) in padded.chunks(512).enumerate() {
+        let block: Vec<_> = block.chunks(32).map(|w| word_from_be_bits(w)).collect();
+
+        h = sha256_compression(cs.namespace(|| format!("block {}", i)), &h, &block)?;
+    }
+
+    Ok(h.iter().flat_map(|w| {
+        let mut bits = w.into_bits_le();
+        bits.reverse();
+        bits
+    }).collect())
+}
+
+fn word_from_be_bits<Var: Copy>(bits: &[Boolean<Var>]) -> UInt32<Var> {
+    let mut bits = bits.to_vec();
+    bits.reverse();
+
+    UInt32::from_bits_le(&bits)
+}
+
+fn sha256_compression<E: Engine, CS: ConstraintSystem<E>>(
+    mut cs: CS,
+    h: &[UInt32<CS::Variable>],
+    block: &[UInt32<CS::Variable>]
+) -> Result<Vec<UInt32<CS::Variable>>, SynthesisError>
+{
+    assert_eq!(h.len(), 8);
+    assert_eq!(block.len(), 16);
+
+    // Message schedule
+    let mut w = block.to_vec();
+    for t in 16..64 {
+        let mut cs = cs.namespace(|| format!("schedule {}", t));
+
+        let s0 = xor3(
+            cs.namespace(|| "s0"),
+            &w[t - 15].rotr(7),
+            &w[t - 15].rotr(18),
+            &w[t - 15].shr(3)
+        )?;
+        let s1 = xor3(
+            cs.namespace(|| "s1"),
+            &w[t - 2].rotr(17),
+            &w[t - 2].rotr(19),
+            &w[t - 2].shr(10)
+        )?;
+
+        let tmp = UInt32::addmany(
+            cs.namespace(|| "w"),
+            &[w[t - 16].clone(), s0, w[t - 7].clone(), s1]
+        )?;
+        w.push(tmp);
+    }
+
+    let mut a = h[0].clone();
+    let mut b = h[1].clone();
+    let mut c = h[2].clone();
+    let mut d = h[3].clone();
+    let mut e = h[4].clone();
+    let mut f = h[5].clone();
+    let mut g = h[6].clone();
+    let mut hh = h[7].clone();
+
+    for t in 0..64 {
+        let mut cs = cs.namespace(|| format!("round {}", t));
+
+        let s1 = xor3(cs.namespace(|| "S1"), &e.rotr(6), &e.rotr(11), &e.rotr(25))?;
+        let ch = ch(cs.namespace(|| "ch"), &e, &f, &g)?;
+        let s0 = xor3(cs.namespace(|| "S0"), &a.rotr(2), &a.rotr(13), &a.rotr(22))?;
+        let maj = maj(cs.namespace(|| "maj"), &a, &b, &c)?;
+
+        // temp1 = h + S1 + ch + k + w
+        // temp2 = S0 + maj
+        // e' = d + temp1
+        // a' = temp1 + temp2
+        let temp1 = [hh, s1, ch, UInt32::constant(ROUND_CONSTANTS[t]), w[t].clone()];
+
+        let new_e = UInt32::addmany(
+            cs.namespace(|| "new e"),
+            &[&[d][..], &temp1[..]].concat()
+        )?;
+        let new_a = UInt32::addmany(
+            cs.namespace(|| "new a"),
+            &[&temp1[..], &[s0, maj][..]].concat()
+        )?;
+
+        hh = g;
+        g = f;
+        f = e;
+        e = new_e;
+        d = c;
+        c = b;
+        b = a;
+        a = new_a;
+    }
+
+    let mut new_h = vec![];
+    for (i, (h, v)) in h.iter().zip([a, b, c, d, e, f, g, hh].iter()).enumerate() {
+        new_h.push(UInt32::addmany(
+            cs.namespace(|| format!("new h {}", i)),
+            &[h.clone(), v.clone()]
+        )?);
+    }
+
+    Ok(new_h)
+}
+
+fn xor3<E: Engine, CS: ConstraintSystem<E>>(
+    mut cs: CS,
+    a: &UInt32<CS::Variable>,
+    b: &UInt32<CS::Variable>,
+    c: &UInt32<CS::Variable>
+) -> Result<UInt32<CS::Variable>, SynthesisError>
+{
+    let bits = a.into_bits_le().iter()
+                .zip(b.into_bits_le().iter())
+                .zip(c.into_bits_le().iter())
+                .enumerate()
+                .map(|(i, ((a, b), c))| {
+                    Boolean::xor3(cs.namespace(|| format!("bit {}", i)), a, b, c)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(UInt32::from_bits_le(&bits))
+}
+
+/// ch(e, f, g) = (e AND f) XOR ((NOT e) AND g),
+/// computed as g XOR (e AND (f XOR g)).
+fn ch<E: Engine, CS: ConstraintSystem<E>>(
+    mut cs: CS,
+    e: &UInt32<CS::Variable>,
+    f: &UInt32<CS::Variable>,
+    g: &UInt32<CS::Variable>
+) -> Result<UInt32<CS::Variable>, SynthesisError>
+{
+    let bits = e.into_bits_le().iter()
+                .zip(f.into_bits_le().iter())
+                .zip(g.into_bits_le().iter())
+                .enumerate()
+                .map(|(i, ((e, f), g))| {
+                    let mut cs = cs.namespace(|| format!("bit {}", i));
+
+                    let tmp = Boolean::xor(cs.namespace(|| "f xor g"), f, g)?;
+                    let tmp = Boolean::and(cs.namespace(|| "e and (f xor g)"), e, &tmp)?;
+
+                    Boolean::xor(cs.namespace(|| "result"), g, &tmp)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(UInt32::from_bits_le(&bits))
+}
+
+/// maj(a, b, c) = (a AND b) XOR (a AND c) XOR (b AND c),
+/// computed as (a AND b) XOR (c AND (a XOR b)).
+fn maj<E: Engine, CS: ConstraintSystem<E>>(
+    mut cs: CS,
+    a: &UInt32<CS::Variable>,
+    b: &UInt32<CS::Variable>,
+    c: &UInt32<CS::Variable>
+) -> Result<UInt32<CS::Variable>, SynthesisError>
+{
+    let bits = a.into_bits_le().iter()
+                .zip(b.into_bits_le().iter())
+                .zip(c.into_bits_le().iter())
+                .enumerate()
+                .map(|(i, ((a, b), c))| {
+                    let mut cs = cs.namespace(|| format!("bit {}", i));
+
+                    let ab = Boolean::and(cs.namespace(|| "a and b"), a, b)?;
+                    let tmp = Boolean::xor(cs.namespace(|| "a xor b"), a, b)?;
+                    let tmp = Boolean::and(cs.namespace(|| "c and (a xor b)"), c, &tmp)?;
+
+                    Boolean::xor(cs.namespace(|| "result"), &ab, &tmp)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(UInt32::from_bits_le(&bits))
+}
+
+#[cfg(test)]
+mod test {
+    use pairing::bls12_381::{Bls12};
+    use ::circuit::boolean::{Boolean, AllocatedBit};
+    use ::circuit::test::TestConstraintSystem;
+    use super::sha256;
+    use bellman::{ConstraintSystem};
+
+    fn check(input: &[u8], input_bits: usize, expected: &str) {
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let bits: Vec<Boolean<_>> = (0..input_bits).map(|i| {
+            let b = (input[i / 8] >> (7 - (i % 8))) & 1 == 1;
+
+            Boolean::from(AllocatedBit::alloc(cs.namespace(|| format!("input bit {}", i)), Some(b)).unwrap())
+        }).collect();
+
+        let hash = sha256(cs.namespace(|| "sha256"), &bits).unwrap();
+
+        assert!(cs.is_satisfied());
+        assert_eq!(hash.len(), 256);
+
+        let hex: String = hash.chunks(4).map(|nibble| {
+            let mut v = 0;
+            for b in nibble {
+                v = (v << 1) | (b.get_value().unwrap() as u32);
+            }
+            format!("{:x}", v)
+        }).collect();
+
+        assert_eq!(hex, expected);
+    }
+
+    #[test]
+    fn test_sha256_nist_vectors() {
+        check(b"", 0, "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        check(b"abc", 24, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+
+        // A 448-bit message pads to two blocks.
+        check(
+            b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+            448,
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+    }
+
+    #[test]
+    fn test_sha256_two_blocks() {
+        check(
+            b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu",
+            896,
+            "cf5b16a778af8380036ce59e7b0492370b249b11e8f07a51afac45037afee9d1"
+        );
+    }
+
+    #[test]
+    fn test_sha256_constraints() {
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+        let input_bits: Vec<_> = (0..256).map(|i| AllocatedBit::alloc(cs.namespace(|| format!("input bit {}", i)), Some(true)).unwrap().into()).collect();
+        sha256(&mut cs, &input_bits).unwrap();
+        assert!(cs.is_satisfied());
+        assert_eq!(cs.num_constraints() - 256, 33334);
+    }
+
+    #[test]
+    fn test_sha256_constant_input() {
+        // A constant input needs no constraints at all.
+        let mut cs = TestConstraintSystem::<Bls12>::new();
+
+        let bits: Vec<Boolean<_>> = b"abc".iter().flat_map(|byte| {
+            (0..8).rev().map(move |i| Boolean::constant((byte >> i) & 1 == 1))
+        }).collect();
+
+        let hash = sha256(&mut cs, &bits).unwrap();
+
+        assert_eq!(cs.num_constraints(), 0);
+        assert_eq!(hash[0].get_value(), Some(true));
+    }
+}
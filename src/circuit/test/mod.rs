@@ -6,7 +6,8 @@ use pairing::{
 use bellman::{
     LinearCombination,
     SynthesisError,
-    ConstraintSystem
+    ConstraintSystem,
+    PublicConstraintSystem
 };
 
 use std::collections::HashMap;
@@ -94,6 +95,14 @@ impl<E: Engine> TestConstraintSystem<E> {
         self.constraints.len()
     }
 
+    /// Returns true if the public inputs, other than
+    /// the constant one, are exactly `expected`.
+    pub fn verify(&self, expected: &[E::Fr]) -> bool
+    {
+        self.inputs.len() == expected.len() + 1 &&
+        self.inputs[1..].iter().zip(expected).all(|(a, b)| a.0 == *b)
+    }
+
     pub fn set(&mut self, path: &str, to: E::Fr)
     {
         match self.named_objects.get(path) {
@@ -204,6 +213,31 @@ impl<E: Engine> ConstraintSystem<E> for TestConstraintSystem<E> {
     }
 }
 
+impl<E: Engine> PublicConstraintSystem<E> for TestConstraintSystem<E> {
+    type PublicRoot = Self;
+
+    fn alloc_input<F, A, AR>(
+        &mut self,
+        annotation: A,
+        f: F
+    ) -> Result<Self::Variable, SynthesisError>
+        where F: FnOnce() -> Result<E::Fr, SynthesisError>, A: FnOnce() -> AR, AR: Into<String>
+    {
+        let index = self.inputs.len();
+        let path = compute_path(&self.current_namespace, annotation().into());
+        self.inputs.push((f()?, path.clone()));
+        let var = Variable::Input(index);
+        self.set_named_obj(path, NamedObject::Var(var));
+
+        Ok(var)
+    }
+
+    fn get_public_root(&mut self) -> &mut Self::PublicRoot
+    {
+        self
+    }
+}
+
 #[test]
 fn test_cs() {
     use pairing::bls12_381::{Bls12, Fr};
@@ -250,4 +284,19 @@ fn test_cs() {
     }
 
     assert!(cs.get("test1/test2/hehe") == Fr::one());
+
+    let d = cs.alloc_input(|| "input", || Ok(Fr::from_str("40").unwrap())).unwrap();
+    cs.enforce(
+        || "input eq",
+        LinearCombination::zero() + c,
+        LinearCombination::zero() + one,
+        LinearCombination::zero() + d
+    );
+
+    assert!(cs.which_is_unsatisfied() == Some("input eq"));
+    cs.set("product", Fr::from_str("40").unwrap());
+    assert!(cs.which_is_unsatisfied() == Some("mult"));
+    assert!(cs.verify(&[Fr::from_str("40").unwrap()]));
+    assert!(!cs.verify(&[Fr::from_str("16").unwrap()]));
+    assert!(!cs.verify(&[]));
 }
@@ -134,6 +134,54 @@ impl<Var: Copy> UInt32<Var> {
         }
     }
 
+    /// Turns this `UInt32` into its little-endian bit order
+    /// representation, least significant bit first.
+    pub fn into_bits_le(&self) -> Vec<Boolean<Var>> {
+        self.bits.clone()
+    }
+
+    /// Converts a little-endian bit order representation of
+    /// bits into a `UInt32`.
+    pub fn from_bits_le(bits: &[Boolean<Var>]) -> Self
+    {
+        assert_eq!(bits.len(), 32);
+
+        let mut value = Some(0u32);
+        for b in bits.iter().rev() {
+            value.as_mut().map(|v| *v <<= 1);
+
+            match b.get_value() {
+                Some(true) => { value.as_mut().map(|v| *v |= 1); },
+                Some(false) => {},
+                None => { value = None }
+            }
+        }
+
+        UInt32 {
+            value: value,
+            bits: bits.to_vec()
+        }
+    }
+
+    /// Shifts right by `by` bits, filling with zeroes.
+    pub fn shr(&self, by: usize) -> Self {
+        let by = if by > 32 { 32 } else { by };
+
+        let fill = Boolean::constant(false);
+
+        let new_bits = self.bits.iter()
+                                .skip(by)
+                                .chain(Some(&fill).into_iter().cycle())
+                                .take(32)
+                                .cloned()
+                                .collect();
+
+        UInt32 {
+            bits: new_bits,
+            value: self.value.map(|v| v.checked_shr(by as u32).unwrap_or(0))
+        }
+    }
+
     pub fn rotr(&self, by: usize) -> Self {
         let by = by % 32;
 
@@ -495,4 +543,56 @@ mod test {
             num = num.rotate_right(1);
         }
     }
+
+    #[test]
+    fn test_uint32_shr() {
+        let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..50 {
+            for i in 0..40 {
+                let num = rng.gen();
+                let a = UInt32::<()>::constant(num);
+                let b = a.shr(i);
+
+                let expected = num.checked_shr(i as u32).unwrap_or(0);
+
+                assert!(b.value.unwrap() == expected);
+
+                let mut tmp = expected;
+                for b in &b.bits {
+                    match b {
+                        &Boolean::Constant(b) => {
+                            assert_eq!(b, tmp & 1 == 1);
+                        },
+                        _ => unreachable!()
+                    }
+
+                    tmp >>= 1;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_uint32_bits_le() {
+        let mut rng = XorShiftRng::from_seed([0x5dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+
+        for _ in 0..1000 {
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let num: u32 = rng.gen();
+            let a = UInt32::alloc(cs.namespace(|| "a"), Some(num)).unwrap();
+
+            let bits = a.into_bits_le();
+            for (i, b) in bits.iter().enumerate() {
+                assert_eq!(b.get_value().unwrap(), (num >> i) & 1 == 1);
+            }
+
+            let b = UInt32::from_bits_le(&bits);
+            assert!(b.value.unwrap() == num);
+
+            let c = UInt32::<()>::from_bits_le(&vec![Boolean::constant(true); 32]);
+            assert!(c.value.unwrap() == 0xffffffff);
+        }
+    }
 }
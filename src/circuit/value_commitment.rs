@@ -0,0 +1,129 @@
+use bellman::{
+    SynthesisError,
+    ConstraintSystem
+};
+
+use jubjub::{
+    JubjubEngine,
+    FixedGenerators
+};
+
+use super::boolean::Boolean;
+use super::ecc::EdwardsPoint;
+
+/// Computes the value commitment
+/// `[value] V + [rcv] R`, matching
+/// `primitives::ValueCommitment::cm`. Both scalars
+/// are in little-endian bit order and are padded
+/// with constant zeroes to a multiple of 3 bits.
+pub fn value_commitment<E: JubjubEngine, CS, Var: Copy>(
+    mut cs: CS,
+    value_bits: &[Boolean<Var>],
+    rcv_bits: &[Boolean<Var>],
+    params: &E::Params
+) -> Result<EdwardsPoint<E, Var>, SynthesisError>
+    where CS: ConstraintSystem<E, Variable=Var>
+{
+    let value = EdwardsPoint::fixed_base_mul(
+        cs.namespace(|| "computation of value"),
+        value_bits,
+        FixedGenerators::ValueCommitmentValue,
+        params
+    )?;
+
+    let rcv = EdwardsPoint::fixed_base_mul(
+        cs.namespace(|| "computation of randomness"),
+        rcv_bits,
+        FixedGenerators::ValueCommitmentRandomness,
+        params
+    )?;
+
+    value.add(
+        cs.namespace(|| "randomization of value commitment"),
+        &rcv,
+        params
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{SeedableRng, Rng, XorShiftRng};
+    use bellman::ConstraintSystem;
+    use pairing::{Field, PrimeField, BitIterator};
+    use pairing::bls12_381::Bls12;
+    use ::circuit::test::*;
+    use ::circuit::boolean::{Boolean, AllocatedBit};
+    use ::jubjub::{JubjubBls12, JubjubParams, FixedGenerators};
+    use ::jubjub::fs::Fs;
+    use ::primitives::ValueCommitment;
+    use super::value_commitment;
+
+    #[test]
+    fn test_value_commitment() {
+        let mut rng = XorShiftRng::from_seed([0x3dbe6259, 0x8d313d76, 0x3237db17, 0xe5bc0654]);
+        let params = &JubjubBls12::new();
+
+        for i in 0..10 {
+            let commitment = ValueCommitment::<Bls12> {
+                value: if i == 0 { 0 } else { rng.gen() },
+                randomness: rng.gen()
+            };
+
+            let value_bits: Vec<bool> = (0..64).map(|j| (commitment.value >> j) & 1 == 1).collect();
+
+            let mut rcv_bits = BitIterator::new(commitment.randomness.into_repr()).collect::<Vec<_>>();
+            rcv_bits.reverse();
+            rcv_bits.truncate(Fs::NUM_BITS as usize);
+
+            let mut cs = TestConstraintSystem::<Bls12>::new();
+
+            let value_bools: Vec<Boolean<_>> = value_bits.iter().enumerate().map(|(j, b)| {
+                Boolean::from(
+                    AllocatedBit::alloc(cs.namespace(|| format!("value {}", j)), Some(*b)).unwrap()
+                )
+            }).collect();
+
+            let rcv_bools: Vec<Boolean<_>> = rcv_bits.iter().enumerate().map(|(j, b)| {
+                Boolean::from(
+                    AllocatedBit::alloc(cs.namespace(|| format!("rcv {}", j)), Some(*b)).unwrap()
+                )
+            }).collect();
+
+            let cv = value_commitment(
+                cs.namespace(|| "value commitment"),
+                &value_bools,
+                &rcv_bools,
+                params
+            ).unwrap();
+
+            assert!(cs.is_satisfied());
+
+            let expected = commitment.cm(params).into_xy();
+            assert_eq!(cv.x.get_value().unwrap(), expected.0);
+            assert_eq!(cv.y.get_value().unwrap(), expected.1);
+        }
+    }
+
+    #[test]
+    fn test_value_commitment_generators() {
+        let params = &JubjubBls12::new();
+
+        let value_base = params.generator(FixedGenerators::ValueCommitmentValue);
+        let randomness_base = params.generator(FixedGenerators::ValueCommitmentRandomness);
+
+        // The bases are not shared with the Pedersen hash
+        // or the note commitment.
+        assert!(value_base != randomness_base);
+        for base in &[value_base, randomness_base] {
+            assert!(*base != params.generator(FixedGenerators::NoteCommitmentRandomization));
+            assert!(params.pedersen_hash_generators().iter().all(|g| g != *base));
+        }
+
+        // The commitment is made with them.
+        let commitment = ValueCommitment::<Bls12> {
+            value: 1,
+            randomness: Fs::one()
+        };
+        assert!(commitment.cm(params) == value_base.add(randomness_base, params));
+    }
+}
@@ -20,6 +20,11 @@ use rand::{
 
 use std::marker::PhantomData;
 
+use std::io::{
+    self,
+    Write
+};
+
 // Represents the affine point (X/Z, Y/Z) via the extended
 // twisted Edwards coordinates.
 pub struct Point<E: JubjubEngine, Subgroup> {
@@ -293,6 +298,31 @@ impl<E: JubjubEngine, Subgroup> Point<E, Subgroup> {
         (x, y)
     }
 
+    /// Serializes the point as the little-endian encoding
+    /// of the y-coordinate, with the sign of the
+    /// x-coordinate stored in the most significant bit.
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W
+    ) -> io::Result<()>
+    {
+        let (x, y) = self.into_xy();
+
+        let mut y_repr = y.into_repr();
+        if x.into_repr().is_odd() {
+            let last = y_repr.as_ref().len() - 1;
+            y_repr.as_mut()[last] |= 1 << 63;
+        }
+
+        for limb in y_repr.as_ref() {
+            for i in 0..8 {
+                writer.write_all(&[(limb >> (i * 8)) as u8])?;
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn negate(&self) -> Self {
         let mut p = self.clone();
 
@@ -23,6 +23,8 @@ use pairing::{
 
 use super::group_hash::group_hash;
 
+use std::sync::OnceLock;
+
 use pairing::bls12_381::{
     Bls12,
     Fr
@@ -34,6 +36,9 @@ pub mod montgomery;
 #[cfg(test)]
 pub mod tests;
 
+#[cfg(test)]
+pub mod toy;
+
 pub trait JubjubEngine: Engine {
     type Fs: PrimeField + SqrtField;
     type Params: JubjubParams<Self>;
@@ -41,8 +46,13 @@ pub trait JubjubEngine: Engine {
 
 pub trait JubjubParams<E: JubjubEngine>: Sized {
     fn edwards_d(&self) -> &E::Fr;
+    /// The coefficient A of the Montgomery form
+    /// y^2 = x^3 + A x^2 + x.
     fn montgomery_a(&self) -> &E::Fr;
     fn montgomery_2a(&self) -> &E::Fr;
+    /// The factor s = sqrt(-(A + 2)) relating the forms:
+    /// the Montgomery point (x, y) is the twisted Edwards
+    /// point (s x / y, (x - 1) / (x + 1)).
     fn scale(&self) -> &E::Fr;
     fn pedersen_hash_generators(&self) -> &[edwards::Point<E, PrimeOrder>];
     fn pedersen_hash_chunks_per_generator(&self) -> usize;
@@ -64,13 +74,29 @@ impl JubjubEngine for Bls12 {
 }
 
 /// Fixed generators of the Jubjub curve of unknown
-/// exponent.
+/// exponent. They are found from group hash tags of
+/// their own, so no two of them, and none of them and
+/// a Pedersen hash generator, are the same point.
 #[derive(Copy, Clone)]
 pub enum FixedGenerators {
     NoteCommitmentRandomization = 0,
-    Max = 1
+    ValueCommitmentValue = 1,
+    ValueCommitmentRandomness = 2,
+    Max = 3
 }
 
+/// The group hash tag prefix for the Pedersen hash
+/// generators, which are found from one-byte tags.
+const PEDERSEN_HASH_TAG: &'static [u8] = b"";
+
+/// The number of Pedersen hash generators that
+/// `JubjubBls12::new` derives.
+const NUM_PEDERSEN_HASH_GENERATORS: usize = 10;
+
+/// The group hash tag prefix for the fixed-base
+/// generators.
+const FIXED_BASE_TAG: &'static [u8] = b"fixed base";
+
 pub struct JubjubBls12 {
     edwards_d: Fr,
     montgomery_a: Fr,
@@ -78,7 +104,9 @@ pub struct JubjubBls12 {
     scale: Fr,
 
     pedersen_hash_generators: Vec<edwards::Point<Bls12, PrimeOrder>>,
-    pedersen_circuit_generators: Vec<Vec<Vec<(Fr, Fr)>>>,
+    // Computed on first use, as most users of the
+    // parameters never synthesize a Pedersen hash.
+    pedersen_circuit_generators: OnceLock<Vec<Vec<Vec<(Fr, Fr)>>>>,
 
     fixed_base_generators: Vec<edwards::Point<Bls12, PrimeOrder>>,
     fixed_base_circuit_generators: Vec<Vec<Vec<(Fr, Fr)>>>,
@@ -99,7 +127,9 @@ impl JubjubParams<Bls12> for JubjubBls12 {
         84
     }
     fn pedersen_circuit_generators(&self) -> &[Vec<Vec<(Fr, Fr)>>] {
-        &self.pedersen_circuit_generators
+        self.pedersen_circuit_generators.get_or_init(|| {
+            self.compute_pedersen_circuit_generators()
+        })
     }
     fn generator(&self, base: FixedGenerators) -> &edwards::Point<Bls12, PrimeOrder>
     {
@@ -128,76 +158,22 @@ impl JubjubBls12 {
             scale: Fr::from_str("17814886934372412843466061268024708274627479829237077604635722030778476050649").unwrap(),
 
             pedersen_hash_generators: vec![],
-            pedersen_circuit_generators: vec![],
+            pedersen_circuit_generators: OnceLock::new(),
 
             fixed_base_generators: vec![],
             fixed_base_circuit_generators: vec![],
         };
 
         // Create the bases for the Pedersen hashes
-        {
-            let mut cur = 0;
-            let mut pedersen_hash_generators = vec![];
-
-            while pedersen_hash_generators.len() < 10 {
-                let gh = group_hash(&[cur], &tmp);
-                // We don't want to overflow and start reusing generators
-                assert!(cur != u8::max_value());
-                cur += 1;
-
-                if let Some(gh) = gh {
-                    pedersen_hash_generators.push(gh);
-                }
-            }
-
-            tmp.pedersen_hash_generators = pedersen_hash_generators;
-        }
-
-        // Create the bases for other parts of the protocol
-        {
-            let mut cur = 0;
-            let mut fixed_base_generators = vec![];
-
-            while fixed_base_generators.len() < (FixedGenerators::Max as usize) {
-                let gh = group_hash(&[cur], &tmp);
-                // We don't want to overflow and start reusing generators
-                assert!(cur != u8::max_value());
-                cur += 1;
-
-                if let Some(gh) = gh {
-                    fixed_base_generators.push(gh);
-                }
-            }
-
-            tmp.fixed_base_generators = fixed_base_generators;
-        }
+        tmp.pedersen_hash_generators = tmp.find_generators(PEDERSEN_HASH_TAG, NUM_PEDERSEN_HASH_GENERATORS);
 
-        // Create the 2-bit window table lookups for each 4-bit
-        // "chunk" in each segment of the Pedersen hash
-        {
-            let mut pedersen_circuit_generators = vec![];
-
-            for mut gen in tmp.pedersen_hash_generators.iter().cloned() {
-                let mut gen = montgomery::Point::from_edwards(&gen, &tmp);
-                let mut windows = vec![];
-                for _ in 0..tmp.pedersen_hash_chunks_per_generator() {
-                    let mut coeffs = vec![];
-                    let mut g = gen.clone();
-                    for _ in 0..4 {
-                        coeffs.push(g.into_xy().expect("cannot produce O"));
-                        g = g.add(&gen, &tmp);
-                    }
-                    windows.push(coeffs);
-
-                    for _ in 0..4 {
-                        gen = gen.double(&tmp);
-                    }
-                }
-                pedersen_circuit_generators.push(windows);
-            }
-
-            tmp.pedersen_circuit_generators = pedersen_circuit_generators;
-        }
+        // Create the bases for other parts of the protocol,
+        // from tags disjoint from the Pedersen hash ones so
+        // that no base is shared between the two.
+        tmp.fixed_base_generators = tmp.find_generators(
+            FIXED_BASE_TAG,
+            FixedGenerators::Max as usize
+        );
 
         // Create the 3-bit window table lookups for fixed-base
         // exp of each base in the protocol.
@@ -225,6 +201,73 @@ impl JubjubBls12 {
 
         tmp
     }
+
+    /// Finds the first `num` generators produced by the
+    /// group hash, trying the tags `prefix || 0`,
+    /// `prefix || 1`, ... in turn.
+    fn find_generators(&self, prefix: &[u8], num: usize) -> Vec<edwards::Point<Bls12, PrimeOrder>> {
+        let mut cur = 0;
+        let mut generators = vec![];
+
+        while generators.len() < num {
+            let mut tag = prefix.to_vec();
+            tag.push(cur);
+
+            let gh = group_hash(&tag, self);
+            // We don't want to overflow and start reusing generators
+            assert!(cur != u8::max_value());
+            cur += 1;
+
+            if let Some(gh) = gh {
+                generators.push(gh);
+            }
+        }
+
+        generators
+    }
+
+    /// Checks that the Pedersen hash generators are all
+    /// of the ones derived from the group hash, and that
+    /// the circuit window tables, if already computed,
+    /// match those generators.
+    pub fn verify_pedersen_circuit_generators(&self) -> bool {
+        let expected = self.find_generators(PEDERSEN_HASH_TAG, NUM_PEDERSEN_HASH_GENERATORS);
+        if expected != self.pedersen_hash_generators {
+            return false;
+        }
+
+        match self.pedersen_circuit_generators.get() {
+            Some(tables) => *tables == self.compute_pedersen_circuit_generators(),
+            None => true
+        }
+    }
+
+    /// Creates the 2-bit window table lookups for each 4-bit
+    /// "chunk" in each segment of the Pedersen hash.
+    fn compute_pedersen_circuit_generators(&self) -> Vec<Vec<Vec<(Fr, Fr)>>> {
+        let mut pedersen_circuit_generators = vec![];
+
+        for gen in self.pedersen_hash_generators.iter() {
+            let mut gen = montgomery::Point::from_edwards(gen, self);
+            let mut windows = vec![];
+            for _ in 0..self.pedersen_hash_chunks_per_generator() {
+                let mut coeffs = vec![];
+                let mut g = gen.clone();
+                for _ in 0..4 {
+                    coeffs.push(g.into_xy().expect("cannot produce O"));
+                    g = g.add(&gen, self);
+                }
+                windows.push(coeffs);
+
+                for _ in 0..4 {
+                    gen = gen.double(self);
+                }
+            }
+            pedersen_circuit_generators.push(windows);
+        }
+
+        pedersen_circuit_generators
+    }
 }
 
 #[test]
@@ -233,3 +276,68 @@ fn test_jubjub_bls12() {
 
     tests::test_suite::<Bls12>(&params);
 }
+
+#[test]
+fn test_fixed_base_generators_distinct() {
+    let params = JubjubBls12::new();
+
+    // Every fixed-base generator is distinct from every
+    // Pedersen hash generator, and from each other.
+    let fixed = &params.fixed_base_generators;
+    assert_eq!(fixed.len(), FixedGenerators::Max as usize);
+
+    for (i, a) in fixed.iter().enumerate() {
+        for b in params.pedersen_hash_generators() {
+            assert!(a != b);
+        }
+
+        for b in &fixed[0..i] {
+            assert!(a != b);
+        }
+    }
+}
+
+#[test]
+fn test_pedersen_circuit_generators_cached() {
+    let params = JubjubBls12::new();
+
+    assert!(params.pedersen_circuit_generators.get().is_none());
+
+    let first = params.pedersen_circuit_generators().as_ptr();
+    let second = params.pedersen_circuit_generators().as_ptr();
+
+    // The tables are built once and then reused.
+    assert_eq!(first, second);
+    assert_eq!(params.pedersen_circuit_generators().len(), params.pedersen_hash_generators().len());
+}
+
+#[test]
+fn test_verify_pedersen_circuit_generators() {
+    let mut params = JubjubBls12::new();
+
+    assert!(params.verify_pedersen_circuit_generators());
+    params.pedersen_circuit_generators();
+    assert!(params.verify_pedersen_circuit_generators());
+
+    // Tampering with a window table is detected.
+    {
+        let mut tampered = JubjubBls12::new();
+        tampered.pedersen_circuit_generators();
+        tampered.pedersen_circuit_generators.get_mut().unwrap()[3][7][1].0.add_assign(&Fr::one());
+
+        assert!(!tampered.verify_pedersen_circuit_generators());
+    }
+
+    // So is dropping generators.
+    for len in 1..NUM_PEDERSEN_HASH_GENERATORS {
+        let mut truncated = JubjubBls12::new();
+        truncated.pedersen_hash_generators.truncate(len);
+
+        assert!(!truncated.verify_pedersen_circuit_generators());
+    }
+
+    // So is replacing a generator.
+    let other = params.pedersen_hash_generators[0].double(&params);
+    params.pedersen_hash_generators[5] = other;
+    assert!(!params.verify_pedersen_circuit_generators());
+}
@@ -0,0 +1,246 @@
+//! A toy pairing engine for tests. It shares the scalar field
+//! of BLS12-381, so Jubjub can be embedded in it unchanged,
+//! but it is a distinct type with no working groups. Gadgets
+//! and hashes that are generic over `JubjubEngine` must work
+//! with it just as they do with `Bls12`.
+
+use pairing::{
+    Engine,
+    PrimeField,
+    PrimeFieldRepr,
+    CurveProjective,
+    CurveAffine,
+    EncodedPoint,
+    GroupDecodingError
+};
+
+use pairing::bls12_381::{
+    Fr,
+    Fq
+};
+
+use rand::{Rand, Rng};
+
+use std::fmt;
+
+use super::{
+    JubjubEngine,
+    JubjubParams,
+    JubjubBls12,
+    FixedGenerators,
+    PrimeOrder,
+    edwards
+};
+
+#[derive(Clone, Debug)]
+pub struct Toy;
+
+/// Stands in for every group of the toy engine. None
+/// of its operations are ever expected to run.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ToyGroup;
+
+fn no_groups() -> ! {
+    unreachable!("the toy engine has no groups")
+}
+
+#[derive(Copy, Clone)]
+pub struct ToyEncoding([u8; 0]);
+
+impl fmt::Display for ToyGroup {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ToyGroup")
+    }
+}
+
+impl Rand for ToyGroup {
+    fn rand<R: Rng>(_: &mut R) -> Self {
+        ToyGroup
+    }
+}
+
+impl CurveProjective for ToyGroup {
+    type Engine = Toy;
+    type Scalar = Fr;
+    type Base = Fq;
+    type Affine = ToyGroup;
+
+    fn zero() -> Self { no_groups() }
+    fn one() -> Self { no_groups() }
+    fn is_zero(&self) -> bool { no_groups() }
+    fn batch_normalization(_: &mut [Self]) { no_groups() }
+    fn is_normalized(&self) -> bool { no_groups() }
+    fn double(&mut self) { no_groups() }
+    fn add_assign(&mut self, _: &Self) { no_groups() }
+    fn add_assign_mixed(&mut self, _: &Self) { no_groups() }
+    fn negate(&mut self) { no_groups() }
+    fn mul_assign<S: Into<<Fr as PrimeField>::Repr>>(&mut self, _: S) { no_groups() }
+    fn into_affine(&self) -> Self { no_groups() }
+    fn recommended_wnaf_for_scalar(_: <Fr as PrimeField>::Repr) -> usize { no_groups() }
+    fn recommended_wnaf_for_num_scalars(_: usize) -> usize { no_groups() }
+}
+
+impl CurveAffine for ToyGroup {
+    type Engine = Toy;
+    type Scalar = Fr;
+    type Base = Fq;
+    type Projective = ToyGroup;
+    type Prepared = ToyGroup;
+    type Uncompressed = ToyEncoding;
+    type Compressed = ToyEncoding;
+    type Pair = ToyGroup;
+    type PairingResult = Fq;
+
+    fn zero() -> Self { no_groups() }
+    fn one() -> Self { no_groups() }
+    fn is_zero(&self) -> bool { no_groups() }
+    fn negate(&mut self) { no_groups() }
+    fn mul<S: Into<<Fr as PrimeField>::Repr>>(&self, _: S) -> Self { no_groups() }
+    fn prepare(&self) -> Self { no_groups() }
+    fn pairing_with(&self, _: &Self) -> Fq { no_groups() }
+    fn into_projective(&self) -> Self { no_groups() }
+}
+
+impl AsRef<[u8]> for ToyEncoding {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsMut<[u8]> for ToyEncoding {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
+}
+
+impl EncodedPoint for ToyEncoding {
+    type Affine = ToyGroup;
+
+    fn empty() -> Self { no_groups() }
+    fn size() -> usize { no_groups() }
+    fn into_affine(&self) -> Result<ToyGroup, GroupDecodingError> { no_groups() }
+    fn into_affine_unchecked(&self) -> Result<ToyGroup, GroupDecodingError> { no_groups() }
+    fn from_affine(_: ToyGroup) -> Self { no_groups() }
+}
+
+impl Engine for Toy {
+    type Fr = Fr;
+    type G1 = ToyGroup;
+    type G1Affine = ToyGroup;
+    type G2 = ToyGroup;
+    type G2Affine = ToyGroup;
+    type Fq = Fq;
+    type Fqe = Fq;
+    type Fqk = Fq;
+
+    fn miller_loop<'a, I>(_: I) -> Fq
+        where I: IntoIterator<Item=&'a (&'a ToyGroup, &'a ToyGroup)>
+    {
+        no_groups()
+    }
+
+    fn final_exponentiation(_: &Fq) -> Option<Fq> {
+        no_groups()
+    }
+}
+
+impl JubjubEngine for Toy {
+    type Fs = super::fs::Fs;
+    type Params = JubjubToy;
+}
+
+pub struct JubjubToy {
+    edwards_d: Fr,
+    montgomery_a: Fr,
+    montgomery_2a: Fr,
+    scale: Fr,
+
+    pedersen_hash_generators: Vec<edwards::Point<Toy, PrimeOrder>>,
+    pedersen_circuit_generators: Vec<Vec<Vec<(Fr, Fr)>>>,
+
+    fixed_base_generators: Vec<edwards::Point<Toy, PrimeOrder>>,
+    fixed_base_circuit_generators: Vec<Vec<Vec<(Fr, Fr)>>>,
+}
+
+impl JubjubParams<Toy> for JubjubToy {
+    fn edwards_d(&self) -> &Fr { &self.edwards_d }
+    fn montgomery_a(&self) -> &Fr { &self.montgomery_a }
+    fn montgomery_2a(&self) -> &Fr { &self.montgomery_2a }
+    fn scale(&self) -> &Fr { &self.scale }
+    fn pedersen_hash_generators(&self) -> &[edwards::Point<Toy, PrimeOrder>] {
+        &self.pedersen_hash_generators
+    }
+    fn pedersen_hash_chunks_per_generator(&self) -> usize {
+        62
+    }
+    fn fixed_base_chunks_per_generator(&self) -> usize {
+        84
+    }
+    fn pedersen_circuit_generators(&self) -> &[Vec<Vec<(Fr, Fr)>>] {
+        &self.pedersen_circuit_generators
+    }
+    fn generator(&self, base: FixedGenerators) -> &edwards::Point<Toy, PrimeOrder>
+    {
+        &self.fixed_base_generators[base as usize]
+    }
+    fn circuit_generators(&self, base: FixedGenerators) -> &[Vec<(Fr, Fr)>]
+    {
+        &self.fixed_base_circuit_generators[base as usize][..]
+    }
+}
+
+impl JubjubToy {
+    /// Carries the Jubjub parameters over from `JubjubBls12`,
+    /// which is possible because the scalar fields agree.
+    pub fn new() -> Self {
+        let bls12 = JubjubBls12::new();
+
+        let mut tmp = JubjubToy {
+            edwards_d: bls12.edwards_d,
+            montgomery_a: bls12.montgomery_a,
+            montgomery_2a: bls12.montgomery_2a,
+            scale: bls12.scale,
+
+            pedersen_hash_generators: vec![],
+            pedersen_circuit_generators: bls12.pedersen_circuit_generators().to_vec(),
+
+            fixed_base_generators: vec![],
+            fixed_base_circuit_generators: bls12.fixed_base_circuit_generators.clone(),
+        };
+
+        fn translate(
+            p: &edwards::Point<::pairing::bls12_381::Bls12, PrimeOrder>,
+            params: &JubjubToy
+        ) -> edwards::Point<Toy, PrimeOrder>
+        {
+            let (x, y) = p.into_xy();
+            let sign = x.into_repr().is_odd();
+
+            edwards::Point::get_for_y(y, sign, params).unwrap()
+                                                    .as_prime_order(params)
+                                                    .unwrap()
+        }
+
+        tmp.pedersen_hash_generators = bls12.pedersen_hash_generators.iter()
+                                            .map(|p| translate(p, &tmp))
+                                            .collect();
+        tmp.fixed_base_generators = bls12.fixed_base_generators.iter()
+                                         .map(|p| translate(p, &tmp))
+                                         .collect();
+
+        tmp
+    }
+}
+
+#[test]
+fn test_jubjub_toy() {
+    let params = JubjubToy::new();
+
+    super::tests::test_suite::<Toy>(&params);
+
+    // The generators are the same points as for Bls12.
+    let bls12 = JubjubBls12::new();
+    for (a, b) in params.pedersen_hash_generators().iter().zip(bls12.pedersen_hash_generators().iter()) {
+        assert_eq!(a.into_xy(), b.into_xy());
+    }
+}
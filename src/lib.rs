@@ -8,3 +8,5 @@ pub mod jubjub;
 pub mod circuit;
 pub mod group_hash;
 pub mod pedersen_hash;
+pub mod poseidon;
+pub mod primitives;
@@ -1,14 +1,49 @@
 use jubjub::*;
 use pairing::*;
 
+/// Domain separation for the Pedersen hash. The
+/// personalization is prepended to the input as
+/// a 6-bit tag, so that hashes in one context can
+/// never collide with hashes in another.
+#[derive(Copy, Clone)]
+pub enum Personalization {
+    NoteCommitment,
+    MerkleTree(usize),
+    /// The rounds of the sponge construction.
+    Sponge(usize)
+}
+
+impl Personalization {
+    pub fn get_bits(&self) -> Vec<bool> {
+        match *self {
+            Personalization::NoteCommitment =>
+                vec![true, true, true, true, true, true],
+            Personalization::MerkleTree(num) => {
+                // The upper half of the tags is reserved
+                // for the sponge.
+                assert!(num < 32);
+
+                (0..6).map(|i| (num >> i) & 1 == 1).collect()
+            },
+            Personalization::Sponge(num) => {
+                // All ones is reserved for note commitments
+                assert!(num < 31);
+
+                (0..6).map(|i| ((num + 32) >> i) & 1 == 1).collect()
+            }
+        }
+    }
+}
+
 pub fn pedersen_hash<E, I>(
+    personalization: Personalization,
     bits: I,
     params: &E::Params
 ) -> edwards::Point<E, PrimeOrder>
     where I: IntoIterator<Item=bool>,
           E: JubjubEngine
 {
-    let mut bits = bits.into_iter();
+    let mut bits = personalization.get_bits().into_iter().chain(bits.into_iter());
 
     let mut result = edwards::Point::zero();
     let mut generators = params.pedersen_hash_generators().iter();
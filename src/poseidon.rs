@@ -0,0 +1,311 @@
+//! The Poseidon permutation, an arithmetization-friendly
+//! alternative to the Pedersen hash. The S-box is x^5 and
+//! the round constants and MDS matrix are derived from the
+//! Grain LFSR exactly as in the reference implementation.
+
+use pairing::{
+    Engine,
+    Field,
+    PrimeField,
+    PrimeFieldRepr
+};
+
+pub struct PoseidonParams<E: Engine> {
+    t: usize,
+    full_rounds: usize,
+    partial_rounds: usize,
+    round_constants: Vec<E::Fr>,
+    mds: Vec<Vec<E::Fr>>
+}
+
+impl<E: Engine> PoseidonParams<E> {
+    /// Generates the parameters for a state of `t`
+    /// elements with the given numbers of rounds.
+    pub fn new(t: usize, full_rounds: usize, partial_rounds: usize) -> Self {
+        assert!(t >= 2);
+        assert!(full_rounds % 2 == 0);
+
+        let mut grain = Grain::new(
+            E::Fr::NUM_BITS as usize,
+            t,
+            full_rounds,
+            partial_rounds
+        );
+
+        let round_constants = (0..((full_rounds + partial_rounds) * t)).map(|_| {
+            // Rejection sampling
+            loop {
+                if let Ok(c) = E::Fr::from_repr(grain.next_repr::<E::Fr>()) {
+                    break c;
+                }
+            }
+        }).collect();
+
+        let mds = loop {
+            let elements: Vec<E::Fr> = (0..(2 * t)).map(|_| {
+                // The values are reduced rather than
+                // rejected, as the reference does.
+                let mut repr = grain.next_repr::<E::Fr>();
+                let modulus = E::Fr::char();
+                if repr >= modulus {
+                    repr.sub_noborrow(&modulus);
+                }
+
+                E::Fr::from_repr(repr).unwrap()
+            }).collect();
+
+            let distinct = (0..elements.len()).all(|i| {
+                (0..i).all(|j| elements[i] != elements[j])
+            });
+            if !distinct {
+                continue;
+            }
+
+            let (xs, ys) = elements.split_at(t);
+
+            // Cauchy matrix: M[i][j] = 1 / (x_i + y_j)
+            let mds: Option<Vec<Vec<E::Fr>>> = xs.iter().map(|x| {
+                ys.iter().map(|y| {
+                    let mut tmp = *x;
+                    tmp.add_assign(y);
+                    tmp.inverse()
+                }).collect()
+            }).collect();
+
+            if let Some(mds) = mds {
+                break mds;
+            }
+        };
+
+        PoseidonParams {
+            t: t,
+            full_rounds: full_rounds,
+            partial_rounds: partial_rounds,
+            round_constants: round_constants,
+            mds: mds
+        }
+    }
+
+    pub fn t(&self) -> usize {
+        self.t
+    }
+
+    pub fn full_rounds(&self) -> usize {
+        self.full_rounds
+    }
+
+    pub fn partial_rounds(&self) -> usize {
+        self.partial_rounds
+    }
+
+    pub fn num_rounds(&self) -> usize {
+        self.full_rounds + self.partial_rounds
+    }
+
+    /// Returns true if every element of the state
+    /// goes through the S-box in this round.
+    pub fn is_full_round(&self, round: usize) -> bool {
+        round < self.full_rounds / 2 ||
+        round >= self.full_rounds / 2 + self.partial_rounds
+    }
+
+    /// The `t` constants added at the start of `round`.
+    pub fn round_constants(&self, round: usize) -> &[E::Fr] {
+        &self.round_constants[(round * self.t)..((round + 1) * self.t)]
+    }
+
+    pub fn mds(&self) -> &[Vec<E::Fr>] {
+        &self.mds
+    }
+}
+
+/// The self-shrinking Grain LFSR used to derive
+/// the parameters.
+struct Grain {
+    state: Vec<bool>
+}
+
+impl Grain {
+    fn new(
+        field_bits: usize,
+        t: usize,
+        full_rounds: usize,
+        partial_rounds: usize
+    ) -> Self
+    {
+        fn append_bits(state: &mut Vec<bool>, value: usize, num_bits: usize) {
+            for i in (0..num_bits).rev() {
+                state.push((value >> i) & 1 == 1);
+            }
+        }
+
+        let mut state = vec![];
+
+        // Prime field, x^alpha S-box
+        append_bits(&mut state, 1, 2);
+        append_bits(&mut state, 0, 4);
+        append_bits(&mut state, field_bits, 12);
+        append_bits(&mut state, t, 12);
+        append_bits(&mut state, full_rounds, 10);
+        append_bits(&mut state, partial_rounds, 10);
+        state.extend((0..30).map(|_| true));
+
+        let mut grain = Grain {
+            state: state
+        };
+
+        for _ in 0..160 {
+            grain.step();
+        }
+
+        grain
+    }
+
+    fn step(&mut self) -> bool {
+        let bit = self.state[62] ^ self.state[51] ^ self.state[38] ^
+                  self.state[23] ^ self.state[13] ^ self.state[0];
+
+        self.state.remove(0);
+        self.state.push(bit);
+
+        bit
+    }
+
+    fn next_bit(&mut self) -> bool {
+        loop {
+            let first = self.step();
+            let second = self.step();
+
+            if first {
+                return second;
+            }
+        }
+    }
+
+    /// Reads `F::NUM_BITS` bits, most significant first.
+    fn next_repr<F: PrimeField>(&mut self) -> F::Repr {
+        let mut repr = F::Repr::default();
+
+        for _ in 0..F::NUM_BITS {
+            repr.mul2();
+            if self.next_bit() {
+                repr.as_mut()[0] |= 1;
+            }
+        }
+
+        repr
+    }
+}
+
+/// Applies the Poseidon permutation to `state`.
+pub fn poseidon_permutation<E: Engine>(
+    state: &mut [E::Fr],
+    params: &PoseidonParams<E>
+)
+{
+    assert_eq!(state.len(), params.t());
+
+    for round in 0..params.num_rounds() {
+        for (s, c) in state.iter_mut().zip(params.round_constants(round)) {
+            s.add_assign(c);
+        }
+
+        let sbox_width = if params.is_full_round(round) { params.t() } else { 1 };
+        for s in state[0..sbox_width].iter_mut() {
+            let mut tmp = *s;
+            tmp.square();
+            tmp.square();
+            s.mul_assign(&tmp);
+        }
+
+        let mixed: Vec<E::Fr> = params.mds().iter().map(|row| {
+            let mut acc = E::Fr::zero();
+            for (m, s) in row.iter().zip(state.iter()) {
+                let mut tmp = *m;
+                tmp.mul_assign(s);
+                acc.add_assign(&tmp);
+            }
+            acc
+        }).collect();
+
+        state.copy_from_slice(&mixed);
+    }
+}
+
+/// Hashes `inputs` with a sponge over the permutation.
+/// The first element of the state is the capacity and
+/// is initialized to the number of inputs; the rest
+/// absorb the inputs, and the hash is the second
+/// element of the final state.
+pub fn poseidon_hash<E: Engine>(
+    inputs: &[E::Fr],
+    params: &PoseidonParams<E>
+) -> E::Fr
+{
+    assert!(inputs.len() > 0);
+
+    let mut state = vec![E::Fr::zero(); params.t()];
+    state[0] = E::Fr::from_repr((inputs.len() as u64).into()).unwrap();
+
+    for chunk in inputs.chunks(params.t() - 1) {
+        for (s, input) in state[1..].iter_mut().zip(chunk.iter()) {
+            s.add_assign(input);
+        }
+
+        poseidon_permutation(&mut state, params);
+    }
+
+    state[1]
+}
+
+#[cfg(test)]
+mod test {
+    use pairing::{Field, PrimeField};
+    use pairing::bls12_381::{Bls12, Fr};
+    use super::*;
+
+    #[test]
+    fn test_poseidon_permutation() {
+        let params = PoseidonParams::<Bls12>::new(3, 8, 57);
+
+        assert_eq!(
+            params.round_constants(0)[0],
+            Fr::from_str("48991097081732275468845314168021420565497297775988823234113406403095118809216").unwrap()
+        );
+
+        // From the reference implementation
+        let mut state = [
+            Fr::from_str("0").unwrap(),
+            Fr::from_str("1").unwrap(),
+            Fr::from_str("2").unwrap()
+        ];
+        poseidon_permutation(&mut state, &params);
+
+        assert_eq!(state, [
+            Fr::from_str("18456658763349757341014058622209659766100673761449600566550821987295786346378").unwrap(),
+            Fr::from_str("37068251774887509885063625701815026138353041152735229476479055620962268601796").unwrap(),
+            Fr::from_str("26763157702141528937904191329664859174584798817251788852101947537759678822298").unwrap()
+        ]);
+    }
+
+    #[test]
+    fn test_poseidon_hash() {
+        let params = PoseidonParams::<Bls12>::new(3, 8, 57);
+
+        let inputs: Vec<Fr> = (0..5).map(|i| Fr::from_str(&i.to_string()).unwrap()).collect();
+
+        // The number of inputs is absorbed, so
+        // padding with zeroes changes the hash.
+        let a = poseidon_hash(&inputs[0..3], &params);
+        let b = poseidon_hash(&[inputs[0], inputs[1], inputs[2], Fr::zero()], &params);
+        assert!(a != b);
+
+        let c = poseidon_hash(&inputs, &params);
+        assert!(a != c);
+
+        // A single chunk is a single permutation.
+        let mut state = [Fr::from_str("2").unwrap(), inputs[3], inputs[4]];
+        poseidon_permutation(&mut state, &params);
+        assert_eq!(poseidon_hash(&inputs[3..5], &params), state[1]);
+    }
+}
@@ -0,0 +1,99 @@
+use pairing::PrimeField;
+
+use blake2::Blake2s;
+use digest::{FixedOutput, Input};
+
+use jubjub::{
+    JubjubEngine,
+    JubjubParams,
+    FixedGenerators,
+    PrimeOrder,
+    edwards
+};
+
+use pedersen_hash::{
+    pedersen_hash,
+    Personalization
+};
+
+/// A commitment to a value, blinded by `randomness`.
+pub struct ValueCommitment<E: JubjubEngine> {
+    pub value: u64,
+    pub randomness: E::Fs
+}
+
+impl<E: JubjubEngine> ValueCommitment<E> {
+    /// Computes `[value] V + [randomness] R` for the
+    /// value commitment generators `V` and `R`.
+    pub fn cm(
+        &self,
+        params: &E::Params
+    ) -> edwards::Point<E, PrimeOrder>
+    {
+        params.generator(FixedGenerators::ValueCommitmentValue)
+              .mul(<E::Fs as PrimeField>::Repr::from(self.value), params)
+              .add(
+                  &params.generator(FixedGenerators::ValueCommitmentRandomness)
+                         .mul(self.randomness, params),
+                  params
+              )
+    }
+}
+
+/// A commitment to the bits of a note, blinded by
+/// `randomness`.
+pub struct NoteCommitment<E: JubjubEngine> {
+    pub content: Vec<bool>,
+    pub randomness: E::Fs
+}
+
+impl<E: JubjubEngine> NoteCommitment<E> {
+    /// Computes the Pedersen hash of the content under
+    /// `Personalization::NoteCommitment`, plus
+    /// `[randomness] R` for the note commitment
+    /// randomization generator `R`.
+    pub fn cm(
+        &self,
+        params: &E::Params
+    ) -> edwards::Point<E, PrimeOrder>
+    {
+        pedersen_hash(
+            Personalization::NoteCommitment,
+            self.content.iter().cloned(),
+            params
+        ).add(
+            &params.generator(FixedGenerators::NoteCommitmentRandomization)
+                   .mul(self.randomness, params),
+            params
+        )
+    }
+}
+
+/// The BLAKE2s personalization for nullifiers.
+pub const NULLIFIER_PERSONALIZATION: &'static [u8; 8] = b"Zcash_nf";
+
+/// Computes the nullifier `BLAKE2s(repr(nk) || rho)`
+/// under `NULLIFIER_PERSONALIZATION`.
+pub fn nullifier<E: JubjubEngine>(
+    nk: &edwards::Point<E, PrimeOrder>,
+    rho: &[u8]
+) -> Vec<u8>
+{
+    let mut preimage = vec![];
+    nk.write(&mut preimage).unwrap();
+    preimage.extend(rho);
+
+    // Digest length, no key, fanout and depth of one
+    let mut param = [0u32; 8];
+    param[0] = 0x01010000 ^ 32;
+    for (i, chunk) in NULLIFIER_PERSONALIZATION.chunks(4).enumerate() {
+        for (j, byte) in chunk.iter().enumerate() {
+            param[6 + i] |= (*byte as u32) << (j * 8);
+        }
+    }
+
+    let mut h = Blake2s::with_parameter_block(&param);
+    h.process(&preimage);
+
+    h.fixed_result().to_vec()
+}